@@ -0,0 +1,103 @@
+use criterion::{BatchSize, BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use verlet::{Boundary, Particle, SimulationConfig, Substeps, Vec2, VerletSimulation};
+
+const DT: f32 = 1.0 / 60.0;
+const PILE_SIZES: [usize; 3] = [1_000, 5_000, 20_000];
+// Share of the container area the particles' bounding squares cover. The
+// radius shrinks with the count so every pile is about half full.
+const FILL: f32 = 0.4;
+const SETTLE_STEPS: usize = 300;
+
+// `count` particles that have come to rest at the bottom of the default circle
+fn settled_pile(count: usize) -> VerletSimulation {
+    let mut config = SimulationConfig {
+        emitters: Vec::new(),
+        max_particles: count,
+        ..SimulationConfig::default()
+    };
+    let Boundary::Circle { center, radius: wall } = config.boundary else {
+        unreachable!("the default boundary is a circle");
+    };
+    let radius = wall * (FILL / count as f32).sqrt();
+    config.particle_radius = radius;
+    // Taller stacks of smaller particles need more substeps to stay stiff,
+    // so keep the default's substeps per radius
+    let defaults = SimulationConfig::default();
+    let Substeps::Fixed(default_steps) = defaults.sub_steps else {
+        unreachable!("the default substeps are fixed");
+    };
+    config.sub_steps = Substeps::Fixed((default_steps as f32 * defaults.particle_radius / radius).ceil() as u32);
+    let mut simulation = VerletSimulation::new(config).unwrap();
+
+    // Fill a square lattice from the bottom of the circle up
+    let spacing = radius * 2.0;
+    let mut y = center.y + wall - spacing;
+    while simulation.particles.len() < count {
+        let mut x = center.x - wall + spacing;
+        while x < center.x + wall && simulation.particles.len() < count {
+            let pos = Vec2::new(x, y);
+            if pos.distance(center) < wall - spacing {
+                simulation.add_particle(Particle::new(pos, pos, radius, 1.0));
+            }
+            x += spacing;
+        }
+        y -= spacing;
+    }
+
+    for _ in 0..SETTLE_STEPS {
+        simulation.step(DT);
+    }
+    simulation
+}
+
+// Each phase runs on a fresh copy of the settled pile, so every iteration does
+// the same work and the copy isn't timed
+fn phases(c: &mut Criterion) {
+    let piles: Vec<_> = PILE_SIZES.iter().map(|&count| (count, settled_pile(count))).collect();
+
+    let mut group = c.benchmark_group("solve_collisions");
+    for (count, pile) in &piles {
+        group.throughput(Throughput::Elements(*count as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(count), pile, |b, pile| {
+            b.iter_batched_ref(|| pile.clone(), |sim| sim.solve_collisions(), BatchSize::LargeInput)
+        });
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("apply_constraints");
+    for (count, pile) in &piles {
+        group.throughput(Throughput::Elements(*count as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(count), pile, |b, pile| {
+            b.iter_batched_ref(|| pile.clone(), |sim| sim.apply_constraints(), BatchSize::LargeInput)
+        });
+    }
+    group.finish();
+
+    // Particles::update, the position Verlet step over every particle
+    let mut group = c.benchmark_group("integration");
+    for (count, pile) in &piles {
+        // Adaptive substeps can pick a different count for each pile
+        let sub_dt = DT / pile.sub_step_count(DT) as f32;
+        group.throughput(Throughput::Elements(*count as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(count), pile, |b, pile| {
+            b.iter_batched_ref(|| pile.clone(), |sim| sim.particles.update(sub_dt), BatchSize::LargeInput)
+        });
+    }
+    group.finish();
+
+    // A whole frame at the frontend's default substeps, emitting and removal
+    // included. Real time at 60 Hz means staying under 16.7 ms per iteration.
+    let mut group = c.benchmark_group("step");
+    for (count, pile) in &piles {
+        let mut pile = pile.clone();
+        pile.config.sub_steps = SimulationConfig::default().sub_steps;
+        group.throughput(Throughput::Elements(*count as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(count), &pile, |b, pile| {
+            b.iter_batched_ref(|| pile.clone(), |sim| sim.step(DT), BatchSize::LargeInput)
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, phases);
+criterion_main!(benches);
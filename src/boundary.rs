@@ -0,0 +1,180 @@
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+use std::f32::consts::TAU;
+
+/// The container particles are kept inside of. The sizes describe the walls
+/// themselves, particles stop when their edge touches them.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Boundary {
+    None,
+    Circle {
+        center: Vec2,
+        radius: f32,
+    },
+    Rect {
+        min: Vec2,
+        max: Vec2,
+    },
+    // Convex, in either winding order
+    Polygon {
+        points: Vec<Vec2>,
+    },
+    Annulus {
+        center: Vec2,
+        inner_radius: f32,
+        outer_radius: f32,
+    },
+}
+
+impl Boundary {
+    /// Returns the normal pointing back inside and the penetration depth if a
+    /// particle at `pos` overlaps the walls.
+    pub fn contact(&self, pos: Vec2, radius: f32) -> Option<(Vec2, f32)> {
+        let corrected = match self {
+            Boundary::None => return None,
+            Boundary::Circle { center, radius: wall } => {
+                let to_obj = pos - *center;
+                let dist = to_obj.length();
+                if dist <= wall - radius {
+                    return None;
+                }
+                *center + to_obj / dist * (wall - radius)
+            }
+            Boundary::Rect { min, max } => pos.max(*min + radius).min(*max - radius),
+            Boundary::Polygon { points } => {
+                // Push out of every edge in turn, which also resolves corners
+                let winding = polygon_winding(points);
+                let mut corrected = pos;
+                for (i, &a) in points.iter().enumerate() {
+                    let b = points[(i + 1) % points.len()];
+                    let inward = (b - a).perp().normalize() * winding;
+                    let dist = (corrected - a).dot(inward);
+                    if dist < radius {
+                        corrected += inward * (radius - dist);
+                    }
+                }
+                corrected
+            }
+            Boundary::Annulus { center, inner_radius, outer_radius } => {
+                let to_obj = pos - *center;
+                let dist = to_obj.length();
+                let n = if dist > 0.0 { to_obj / dist } else { Vec2::X };
+                if dist > outer_radius - radius {
+                    *center + n * (outer_radius - radius)
+                } else if dist < inner_radius + radius {
+                    *center + n * (inner_radius + radius)
+                } else {
+                    return None;
+                }
+            }
+        };
+
+        let correction = corrected - pos;
+        let depth = correction.length();
+        if depth > 0.0 {
+            Some((correction / depth, depth))
+        } else {
+            None
+        }
+    }
+
+    /// Flags every particle that may overlap the walls, so [`Boundary::contact`]
+    /// only runs for those. The shape is matched once and each arm is a plain
+    /// loop over the coordinates, which vectorizes.
+    pub(crate) fn flag_contacts(&self, x: &[f32], y: &[f32], radius: &[f32], flags: &mut Vec<bool>) {
+        let n = x.len();
+        flags.clear();
+        flags.resize(n, false);
+        let (y, radius, flags) = (&y[..n], &radius[..n], &mut flags[..n]);
+
+        match self {
+            Boundary::None => {}
+            Boundary::Circle { center, radius: wall } => {
+                for i in 0..n {
+                    let (dx, dy) = (x[i] - center.x, y[i] - center.y);
+                    flags[i] = (dx * dx + dy * dy).sqrt() > wall - radius[i];
+                }
+            }
+            Boundary::Rect { min, max } => {
+                for i in 0..n {
+                    let r = radius[i];
+                    flags[i] = (x[i] < min.x + r) | (x[i] > max.x - r) | (y[i] < min.y + r) | (y[i] > max.y - r);
+                }
+            }
+            Boundary::Annulus { center, inner_radius, outer_radius } => {
+                for i in 0..n {
+                    let (dx, dy) = (x[i] - center.x, y[i] - center.y);
+                    let dist = (dx * dx + dy * dy).sqrt();
+                    flags[i] = (dist > outer_radius - radius[i]) | (dist < inner_radius + radius[i]);
+                }
+            }
+            // Too many edges to be worth a separate test
+            Boundary::Polygon { .. } => flags.fill(true),
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        let ok = match self {
+            Boundary::None => true,
+            Boundary::Circle { center, radius } => center.is_finite() && *radius > 0.0,
+            Boundary::Rect { min, max } => min.is_finite() && max.is_finite() && max.cmpgt(*min).all(),
+            Boundary::Polygon { points } => is_convex_polygon(points),
+            Boundary::Annulus { center, inner_radius, outer_radius } => {
+                center.is_finite() && *inner_radius >= 0.0 && outer_radius > inner_radius
+            }
+        };
+
+        if ok {
+            Ok(())
+        } else {
+            Err(format!("degenerate boundary: {self:?}"))
+        }
+    }
+}
+
+// At least three finite points without repeats, turning the same way at
+// every corner and going around exactly once
+pub(crate) fn is_convex_polygon(points: &[Vec2]) -> bool {
+    let n = points.len();
+    if n < 3 || !points.iter().all(|p| p.is_finite()) {
+        return false;
+    }
+
+    let mut sign = 0.0;
+    let mut turning = 0.0;
+    for i in 0..n {
+        let a = points[(i + 1) % n] - points[i];
+        let b = points[(i + 2) % n] - points[(i + 1) % n];
+        if a == Vec2::ZERO {
+            return false;
+        }
+
+        let cross = a.perp_dot(b);
+        if sign * cross < 0.0 {
+            return false;
+        }
+        if cross != 0.0 {
+            sign = cross.signum();
+        }
+        turning += cross.atan2(a.dot(b));
+    }
+    (turning.abs() - TAU).abs() < 1e-3
+}
+
+// 1.0 for counter-clockwise points in a y-up frame, -1.0 for clockwise
+pub(crate) fn polygon_winding(points: &[Vec2]) -> f32 {
+    let mut area = 0.0;
+    for (i, &a) in points.iter().enumerate() {
+        let b = points[(i + 1) % points.len()];
+        area += a.perp_dot(b);
+    }
+
+    if area > 0.0 {
+        1.0
+    } else if area < 0.0 {
+        -1.0
+    } else {
+        0.0
+    }
+}
@@ -0,0 +1,112 @@
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+
+use crate::boundary::{is_convex_polygon, polygon_winding};
+
+/// Solid static geometry inside the world, for funnels, pegs and ramps.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Collider {
+    Segment {
+        a: Vec2,
+        b: Vec2,
+    },
+    Capsule {
+        a: Vec2,
+        b: Vec2,
+        radius: f32,
+    },
+    // Convex, in either winding order
+    Polygon {
+        points: Vec<Vec2>,
+    },
+    Circle {
+        center: Vec2,
+        radius: f32,
+    },
+}
+
+impl Collider {
+    /// Returns the normal pointing away from the collider and the penetration
+    /// depth if a particle at `pos` overlaps it.
+    pub fn contact(&self, pos: Vec2, radius: f32) -> Option<(Vec2, f32)> {
+        match self {
+            Collider::Segment { a, b } => round_contact(pos, closest_on_segment(pos, *a, *b), radius, *b - *a),
+            Collider::Capsule { a, b, radius: thickness } => {
+                round_contact(pos, closest_on_segment(pos, *a, *b), radius + thickness, *b - *a)
+            }
+            Collider::Circle { center, radius: size } => round_contact(pos, *center, radius + size, Vec2::X),
+            Collider::Polygon { points } => {
+                let winding = polygon_winding(points);
+
+                // Find the edge the particle is least inside of
+                let mut best = (f32::MIN, Vec2::ZERO);
+                for (i, &a) in points.iter().enumerate() {
+                    let b = points[(i + 1) % points.len()];
+                    let outward = -(b - a).perp().normalize() * winding;
+                    let dist = (pos - a).dot(outward);
+                    if dist > best.0 {
+                        best = (dist, outward);
+                    }
+                }
+
+                let (dist, outward) = best;
+                if dist <= 0.0 {
+                    // Center inside the polygon, push out through the closest edge
+                    return Some((outward, radius - dist));
+                }
+
+                // Center outside, collide with the closest point on the outline
+                let closest = points
+                    .iter()
+                    .enumerate()
+                    .map(|(i, &a)| closest_on_segment(pos, a, points[(i + 1) % points.len()]))
+                    .min_by(|p, q| pos.distance_squared(*p).total_cmp(&pos.distance_squared(*q)))?;
+                round_contact(pos, closest, radius, outward.perp())
+            }
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        let ok = match self {
+            Collider::Segment { a, b } => a.is_finite() && b.is_finite(),
+            Collider::Capsule { a, b, radius } => a.is_finite() && b.is_finite() && *radius >= 0.0,
+            Collider::Circle { center, radius } => center.is_finite() && *radius > 0.0,
+            Collider::Polygon { points } => is_convex_polygon(points),
+        };
+
+        if ok {
+            Ok(())
+        } else {
+            Err(format!("degenerate collider: {self:?}"))
+        }
+    }
+}
+
+fn closest_on_segment(pos: Vec2, a: Vec2, b: Vec2) -> Vec2 {
+    let ab = b - a;
+    let len_sq = ab.length_squared();
+    if len_sq == 0.0 {
+        return a;
+    }
+
+    let t = ((pos - a).dot(ab) / len_sq).clamp(0.0, 1.0);
+    a + ab * t
+}
+
+// Contact between a particle and a point thickened to `min_dist`. `tangent` is
+// only used to pick a direction when the particle sits exactly on the point.
+fn round_contact(pos: Vec2, closest: Vec2, min_dist: f32, tangent: Vec2) -> Option<(Vec2, f32)> {
+    let to_obj = pos - closest;
+    let dist = to_obj.length();
+    if dist >= min_dist {
+        return None;
+    }
+
+    let n = if dist > 0.0 {
+        to_obj / dist
+    } else {
+        tangent.perp().normalize_or(Vec2::Y)
+    };
+    Some((n, min_dist - dist))
+}
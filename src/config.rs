@@ -0,0 +1,157 @@
+use glam::Vec2;
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use crate::boundary::Boundary;
+use crate::collider::Collider;
+use crate::emitter::Emitter;
+use crate::kill_zone::KillZone;
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Io(std::io::Error),
+    Parse(String),
+    Invalid(String),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Io(err) => write!(f, "could not read config: {err}"),
+            ConfigError::Parse(msg) => write!(f, "could not parse config: {msg}"),
+            ConfigError::Invalid(msg) => write!(f, "invalid config: {msg}"),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl From<std::io::Error> for ConfigError {
+    fn from(err: std::io::Error) -> Self {
+        ConfigError::Io(err)
+    }
+}
+
+/// How many substeps each step is split into.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Substeps {
+    Fixed(u32),
+    // Picks the count each step so the fastest particle moves at most
+    // `max_travel` times the smallest radius per substep
+    Adaptive { min: u32, max: u32, max_travel: f32 },
+}
+
+impl Substeps {
+    pub fn adaptive() -> Self {
+        Substeps::Adaptive {
+            min: 2,
+            max: 32,
+            max_travel: 0.5,
+        }
+    }
+}
+
+/// Parameters of a simulation. Missing fields in a config file fall back to
+/// the defaults, unknown ones are an error so typos don't go unnoticed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SimulationConfig {
+    pub width: f32,
+    pub height: f32,
+    pub gravity: Vec2,
+    pub particle_radius: f32,
+    pub particle_mass: f32,
+    pub boundary: Boundary,
+    pub colliders: Vec<Collider>,
+    // Fraction of the velocity kept when bouncing off the boundary
+    pub damping: f32,
+    pub sub_steps: Substeps,
+    pub max_particles: usize,
+    pub emitters: Vec<Emitter>,
+    pub kill_zones: Vec<KillZone>,
+    pub seed: u64,
+}
+
+impl Default for SimulationConfig {
+    fn default() -> Self {
+        SimulationConfig {
+            width: 600.0,
+            height: 600.0,
+            gravity: Vec2::new(0.0, 750.0),
+            particle_radius: 7.0,
+            particle_mass: 1.0,
+            boundary: Boundary::Circle {
+                center: Vec2::new(300.0, 300.0),
+                radius: 243.0,
+            },
+            colliders: Vec::new(),
+            damping: 0.999,
+            sub_steps: Substeps::Fixed(6),
+            max_particles: 1000,
+            emitters: vec![Emitter::default()],
+            kill_zones: Vec::new(),
+            seed: 0,
+        }
+    }
+}
+
+impl SimulationConfig {
+    /// Loads a config from a `.toml` or `.json` file and validates it.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path)?;
+
+        let config: SimulationConfig = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => toml::from_str(&text).map_err(|err| ConfigError::Parse(err.to_string()))?,
+            Some("json") => serde_json::from_str(&text).map_err(|err| ConfigError::Parse(err.to_string()))?,
+            _ => return Err(ConfigError::Parse(format!("unknown config format: {}", path.display()))),
+        };
+
+        config.validate()?;
+        Ok(config)
+    }
+
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        fn check(ok: bool, msg: &str) -> Result<(), ConfigError> {
+            if ok { Ok(()) } else { Err(ConfigError::Invalid(msg.to_string())) }
+        }
+
+        check(
+            self.width > 0.0 && self.height > 0.0 && self.width.is_finite() && self.height.is_finite(),
+            "width and height must be positive",
+        )?;
+        check(self.gravity.is_finite(), "gravity must be finite")?;
+        check(
+            self.particle_radius > 0.0 && self.particle_radius.is_finite(),
+            "particle_radius must be positive",
+        )?;
+        check(
+            self.particle_mass > 0.0 && self.particle_mass.is_finite(),
+            "particle_mass must be positive",
+        )?;
+        self.boundary.validate().map_err(ConfigError::Invalid)?;
+        for collider in &self.colliders {
+            collider.validate().map_err(ConfigError::Invalid)?;
+        }
+        check((0.0..=1.0).contains(&self.damping), "damping must be between 0 and 1")?;
+        match self.sub_steps {
+            Substeps::Fixed(count) => check(count > 0, "sub_steps must be at least 1")?,
+            Substeps::Adaptive { min, max, max_travel } => {
+                check(min > 0 && max >= min, "adaptive sub_steps need 1 <= min <= max")?;
+                check(max_travel > 0.0, "adaptive sub_steps need a positive max_travel")?;
+            }
+        }
+        for emitter in &self.emitters {
+            emitter.validate().map_err(ConfigError::Invalid)?;
+        }
+        for kill_zone in &self.kill_zones {
+            kill_zone.validate().map_err(ConfigError::Invalid)?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,42 @@
+use serde::{Deserialize, Serialize};
+
+use crate::particles::Particles;
+
+/// Keeps particles `a` and `b` at `rest_length` from each other. A stiffness
+/// of 1.0 fully corrects the length every substep, lower values give stretch.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct DistanceConstraint {
+    pub a: usize,
+    pub b: usize,
+    pub rest_length: f32,
+    pub stiffness: f32,
+}
+
+impl DistanceConstraint {
+    pub fn new(a: usize, b: usize, rest_length: f32, stiffness: f32) -> Self {
+        DistanceConstraint {
+            a,
+            b,
+            rest_length,
+            stiffness,
+        }
+    }
+
+    pub fn solve(&self, particles: &mut Particles) {
+        let (w1, w2) = (particles.inverse_mass(self.a), particles.inverse_mass(self.b));
+        let (p1, p2) = (particles.pos(self.a), particles.pos(self.b));
+
+        let total_inverse_mass = w1 + w2;
+        let delta = p2 - p1;
+        let dist = delta.length();
+        if total_inverse_mass == 0.0 || dist == 0.0 {
+            return;
+        }
+
+        // Split the correction by mass, like the contact response
+        let correction = delta * ((dist - self.rest_length) / dist * self.stiffness / total_inverse_mass);
+
+        particles.set_pos(self.a, p1 + correction * w1);
+        particles.set_pos(self.b, p2 - correction * w2);
+    }
+}
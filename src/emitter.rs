@@ -0,0 +1,147 @@
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+use std::f32::consts::PI;
+
+use crate::particle::Particle;
+use crate::particles::Particles;
+use crate::rng::Rng;
+
+/// The particle an emitter spawns.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ParticleTemplate {
+    pub radius: f32,
+    pub mass: f32,
+    // Frames each particle lives for, forever if unset
+    pub lifetime: Option<u32>,
+    pub color: Option<[u8; 4]>,
+}
+
+impl Default for ParticleTemplate {
+    fn default() -> Self {
+        ParticleTemplate {
+            radius: 7.0,
+            mass: 1.0,
+            lifetime: None,
+            color: None,
+        }
+    }
+}
+
+/// How the angle of each spawned particle is picked within the spread.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SprayPattern {
+    // Uniformly random, drawn from the simulation's seeded stream
+    Random,
+    // Sweeps from one edge of the spread to the other and back every `period` particles
+    Sweep { period: u32 },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Emitter {
+    pub position: Vec2,
+    // Angle of the spray in radians, and how far particles may deviate from it
+    pub direction: f32,
+    pub spread: f32,
+    // Units per second
+    pub speed: f32,
+    // Frames between bursts, and particles per burst
+    pub interval: u32,
+    pub burst: u32,
+    // Frames until the emitter removes itself, forever if unset
+    pub lifetime: Option<u64>,
+    pub pattern: SprayPattern,
+    pub template: ParticleTemplate,
+    pub age: u64,
+    pub emitted: u64,
+}
+
+impl Default for Emitter {
+    fn default() -> Self {
+        Emitter::fountain(Vec2::new(300.0, 100.0), ParticleTemplate::default())
+    }
+}
+
+impl Emitter {
+    /// The original fountain: one particle per frame, sweeping back and forth
+    /// through a two radian wide arc pointing down.
+    pub fn fountain(position: Vec2, template: ParticleTemplate) -> Self {
+        Emitter {
+            position,
+            direction: 5.0 - PI,
+            spread: 1.0,
+            speed: 1440.0,
+            interval: 1,
+            burst: 1,
+            lifetime: None,
+            pattern: SprayPattern::Sweep { period: 40 },
+            template,
+            age: 0,
+            emitted: 0,
+        }
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.lifetime.is_some_and(|lifetime| self.age >= lifetime)
+    }
+
+    /// Advances the emitter by a frame, spawning into `particles` while they
+    /// are below `max_particles`. `sub_dt` is the substep length the new
+    /// particles start out with.
+    pub fn emit(&mut self, particles: &mut Particles, max_particles: usize, sub_dt: f32, rng: &mut Rng) {
+        self.age += 1;
+        if self.is_expired() || !self.age.is_multiple_of(self.interval as u64) {
+            return;
+        }
+
+        for _ in 0..self.burst {
+            if particles.len() >= max_particles {
+                break;
+            }
+
+            let offset = match self.pattern {
+                SprayPattern::Random => rng.range(-self.spread, self.spread),
+                SprayPattern::Sweep { period } => {
+                    // Triangle wave between -spread and spread
+                    let half = period as f32 / 2.0;
+                    let t = (self.emitted % period as u64) as f32;
+                    let t = if t <= half { t } else { period as f32 - t };
+                    (t / half * 2.0 - 1.0) * self.spread
+                }
+            };
+
+            // Velocity is stored as the distance moved in one substep
+            let vel = Vec2::from_angle(self.direction + offset) * self.speed * sub_dt;
+            let mut particle = Particle::new(
+                self.position,
+                self.position - vel,
+                self.template.radius,
+                self.template.mass,
+            );
+            particle.lifetime = self.template.lifetime;
+            particle.color = self.template.color;
+            particles.push(particle);
+            self.emitted += 1;
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        let ok = self.position.is_finite()
+            && self.direction.is_finite()
+            && self.spread.is_finite()
+            && self.speed.is_finite()
+            && self.interval > 0
+            && !matches!(self.pattern, SprayPattern::Sweep { period: 0 })
+            && self.template.radius > 0.0
+            && self.template.mass > 0.0
+            && self.template.mass.is_finite();
+
+        if ok {
+            Ok(())
+        } else {
+            Err(format!("invalid emitter: {self:?}"))
+        }
+    }
+}
@@ -0,0 +1,75 @@
+use macroquad::prelude::*;
+
+// Quads per mesh. macroquad starts a new draw call at 5000 indices, six per
+// quad, so this is about as many as fit in one
+const QUADS_PER_MESH: usize = 800;
+const DISC_TEXTURE_SIZE: u16 = 64;
+
+/// Draws discs as textured quads gathered into a few large meshes, instead
+/// of tessellating a circle for each one. The meshes are kept between frames
+/// so their buffers are only allocated once.
+pub struct DiscBatch {
+    texture: Texture2D,
+    meshes: Vec<Mesh>,
+    len: usize,
+}
+
+impl DiscBatch {
+    pub fn new() -> Self {
+        DiscBatch {
+            texture: disc_texture(DISC_TEXTURE_SIZE),
+            meshes: Vec::new(),
+            len: 0,
+        }
+    }
+
+    pub fn push(&mut self, center: Vec2, radius: f32, color: Color) {
+        let index = self.len / QUADS_PER_MESH;
+        if index == self.meshes.len() {
+            self.meshes.push(Mesh {
+                vertices: Vec::with_capacity(QUADS_PER_MESH * 4),
+                indices: Vec::with_capacity(QUADS_PER_MESH * 6),
+                texture: Some(self.texture.clone()),
+            });
+        }
+        let mesh = &mut self.meshes[index];
+
+        let first = mesh.vertices.len() as u16;
+        let (min, max) = (center - radius, center + radius);
+        mesh.vertices.extend([
+            Vertex::new(min.x, min.y, 0.0, 0.0, 0.0, color),
+            Vertex::new(max.x, min.y, 0.0, 1.0, 0.0, color),
+            Vertex::new(max.x, max.y, 0.0, 1.0, 1.0, color),
+            Vertex::new(min.x, max.y, 0.0, 0.0, 1.0, color),
+        ]);
+        mesh.indices.extend([0, 1, 2, 0, 2, 3].map(|i| first + i));
+        self.len += 1;
+    }
+
+    /// Draws everything pushed since the last call and empties the batch.
+    pub fn draw(&mut self) {
+        for mesh in self.meshes.iter_mut().filter(|mesh| !mesh.vertices.is_empty()) {
+            draw_mesh(mesh);
+            mesh.vertices.clear();
+            mesh.indices.clear();
+        }
+        self.len = 0;
+    }
+}
+
+// A white disc filling the texture, with a one pixel soft edge
+fn disc_texture(size: u16) -> Texture2D {
+    let mut image = Image::gen_image_color(size, size, Color::new(1.0, 1.0, 1.0, 0.0));
+    let radius = size as f32 / 2.0;
+    for y in 0..size as u32 {
+        for x in 0..size as u32 {
+            let dist = vec2(x as f32 + 0.5, y as f32 + 0.5).distance(vec2(radius, radius));
+            let alpha = (radius - dist).clamp(0.0, 1.0);
+            image.set_pixel(x, y, Color::new(1.0, 1.0, 1.0, alpha));
+        }
+    }
+
+    let texture = Texture2D::from_image(&image);
+    texture.set_filter(FilterMode::Linear);
+    texture
+}
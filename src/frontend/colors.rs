@@ -0,0 +1,211 @@
+use macroquad::prelude::*;
+use verlet::VerletSimulation;
+
+const PLAIN: Color = Color::new(1.0, 1.0, 1.0, 1.0);
+const PINNED: Color = Color::new(1.0, 100.0 / 255.0, 100.0 / 255.0, 1.0);
+// The speed and pressure scales follow the largest value, shrinking by this
+// much per frame once it drops so the colours don't flicker
+const RANGE_DECAY: f32 = 0.98;
+// Neighbours of a particle in a hexagonal packing of equal sizes
+const MAX_CONTACTS: u32 = 6;
+const LEGEND_SIZE: Vec2 = Vec2::new(200.0, 12.0);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ColorMode {
+    Plain,
+    // Length of the velocity
+    Speed,
+    // Spawn order by particle id, oldest first
+    Index,
+    Contacts,
+    // Overlap with the neighbours relative to the radius
+    Pressure,
+    // The particle's own colour, plain if it has none
+    User,
+}
+
+impl ColorMode {
+    pub const ALL: [ColorMode; 6] = [
+        ColorMode::Plain,
+        ColorMode::Speed,
+        ColorMode::Index,
+        ColorMode::Contacts,
+        ColorMode::Pressure,
+        ColorMode::User,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ColorMode::Plain => "Plain",
+            ColorMode::Speed => "Speed",
+            ColorMode::Index => "Index",
+            ColorMode::Contacts => "Contacts",
+            ColorMode::Pressure => "Pressure",
+            ColorMode::User => "User",
+        }
+    }
+}
+
+/// Gradient the scalar colour modes map their values through.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Palette {
+    Viridis,
+    Heat,
+    Rainbow,
+    Grayscale,
+}
+
+impl Palette {
+    pub const ALL: [Palette; 4] = [Palette::Viridis, Palette::Heat, Palette::Rainbow, Palette::Grayscale];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Palette::Viridis => "Viridis",
+            Palette::Heat => "Heat",
+            Palette::Rainbow => "Rainbow",
+            Palette::Grayscale => "Grayscale",
+        }
+    }
+
+    // Evenly spaced colour stops from 0 to 1
+    fn stops(self) -> &'static [[u8; 3]] {
+        match self {
+            Palette::Viridis => &[[68, 1, 84], [59, 82, 139], [33, 145, 140], [94, 201, 98], [253, 231, 37]],
+            Palette::Heat => &[[20, 11, 52], [132, 32, 107], [229, 92, 48], [246, 215, 70], [252, 255, 164]],
+            Palette::Rainbow => &[[110, 64, 170], [40, 120, 240], [30, 200, 140], [170, 220, 50], [255, 140, 40], [230, 40, 60]],
+            Palette::Grayscale => &[[60, 60, 60], [255, 255, 255]],
+        }
+    }
+
+    /// Colour at `t`, clamped to 0..=1.
+    pub fn sample(self, t: f32) -> Color {
+        let stops = self.stops();
+        let x = t.clamp(0.0, 1.0) * (stops.len() - 1) as f32;
+        let i = (x as usize).min(stops.len() - 2);
+        let (a, b, f) = (stops[i], stops[i + 1], x - i as f32);
+
+        let channel = |k: usize| (a[k] as f32 + (b[k] as f32 - a[k] as f32) * f) / 255.0;
+        Color::new(channel(0), channel(1), channel(2), 1.0)
+    }
+}
+
+/// How particles are coloured. C cycles the mode and V the palette, the
+/// parameter panel has both as drop downs.
+pub struct Coloring {
+    pub mode: ColorMode,
+    pub palette: Palette,
+    speed_range: f32,
+    pressure_range: f32,
+    // Oldest and newest particle ids alive
+    ids: (u64, u64),
+}
+
+impl Default for Coloring {
+    fn default() -> Self {
+        Coloring {
+            mode: ColorMode::Plain,
+            palette: Palette::Viridis,
+            speed_range: 0.0,
+            pressure_range: 0.0,
+            ids: (0, 0),
+        }
+    }
+}
+
+impl Coloring {
+    pub fn handle_keys(&mut self) {
+        if is_key_pressed(KeyCode::C) {
+            self.mode = next(&ColorMode::ALL, self.mode);
+        }
+        if is_key_pressed(KeyCode::V) {
+            self.palette = next(&Palette::ALL, self.palette);
+        }
+    }
+
+    /// Updates the auto scales to the current state. Call once per frame
+    /// before [`Coloring::color`].
+    pub fn prepare(&mut self, simulation: &VerletSimulation) {
+        let particles = &simulation.particles;
+        match self.mode {
+            ColorMode::Speed => {
+                let max = (0..particles.len()).map(|i| simulation.velocity(i).length()).fold(0.0, f32::max);
+                self.speed_range = max.max(self.speed_range * RANGE_DECAY);
+            }
+            ColorMode::Pressure => {
+                let max = (0..particles.len()).map(|i| relative_pressure(simulation, i)).fold(0.0, f32::max);
+                self.pressure_range = max.max(self.pressure_range * RANGE_DECAY);
+            }
+            ColorMode::Index => {
+                let ids = particles.ids();
+                self.ids = (ids.iter().copied().min().unwrap_or(0), ids.iter().copied().max().unwrap_or(0));
+            }
+            _ => {}
+        }
+    }
+
+    pub fn color(&self, simulation: &VerletSimulation, index: usize) -> Color {
+        let particles = &simulation.particles;
+        if self.mode == ColorMode::User
+            && let Some([r, g, b, a]) = particles.color(index)
+        {
+            return Color::from_rgba(r, g, b, a);
+        }
+        if particles.is_pinned(index) {
+            return PINNED;
+        }
+
+        let t = match self.mode {
+            ColorMode::Plain | ColorMode::User => return PLAIN,
+            ColorMode::Speed => fraction(simulation.velocity(index).length(), self.speed_range),
+            ColorMode::Index => {
+                let (oldest, newest) = self.ids;
+                fraction(particles.id(index).saturating_sub(oldest) as f32, (newest - oldest) as f32)
+            }
+            ColorMode::Contacts => fraction(particles.contacts(index) as f32, MAX_CONTACTS as f32),
+            ColorMode::Pressure => fraction(relative_pressure(simulation, index), self.pressure_range),
+        };
+        self.palette.sample(t)
+    }
+
+    /// Draws the scale of the current mode in the bottom left corner.
+    pub fn draw_legend(&self) {
+        let (title, min, max) = match self.mode {
+            ColorMode::Plain | ColorMode::User => return,
+            ColorMode::Speed => ("Speed (units/s)", "0".to_string(), format!("{:.0}", self.speed_range)),
+            ColorMode::Index => ("Spawn order (particle id)", self.ids.0.to_string(), self.ids.1.to_string()),
+            ColorMode::Contacts => ("Contacts", "0".to_string(), MAX_CONTACTS.to_string()),
+            ColorMode::Pressure => (
+                "Pressure (overlap / radius)",
+                "0".to_string(),
+                format!("{:.2}", self.pressure_range),
+            ),
+        };
+
+        let origin = vec2(10.0, screen_height() - 40.0);
+        let segments = 64;
+        let width = LEGEND_SIZE.x / segments as f32;
+        for i in 0..segments {
+            let color = self.palette.sample(i as f32 / (segments - 1) as f32);
+            draw_rectangle(origin.x + i as f32 * width, origin.y, width + 0.5, LEGEND_SIZE.y, color);
+        }
+
+        let text = Color::from_rgba(255, 255, 255, 255);
+        draw_text(title, origin.x, origin.y - 6.0, 20.0, text);
+        draw_text(&min, origin.x, origin.y + LEGEND_SIZE.y + 16.0, 18.0, text);
+        let size = measure_text(&max, None, 18, 1.0);
+        draw_text(&max, origin.x + LEGEND_SIZE.x - size.width, origin.y + LEGEND_SIZE.y + 16.0, 18.0, text);
+    }
+}
+
+fn relative_pressure(simulation: &VerletSimulation, index: usize) -> f32 {
+    simulation.particles.pressure(index) / simulation.particles.radius(index)
+}
+
+fn fraction(value: f32, max: f32) -> f32 {
+    if max > 0.0 { value / max } else { 0.0 }
+}
+
+fn next<T: Copy + PartialEq>(all: &[T], current: T) -> T {
+    let i = all.iter().position(|&item| item == current).unwrap_or(0);
+    all[(i + 1) % all.len()]
+}
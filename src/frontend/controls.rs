@@ -0,0 +1,100 @@
+use macroquad::prelude::*;
+use verlet::FixedTimestep;
+
+pub const MIN_TIME_SCALE: f32 = 1.0 / 16.0;
+pub const MAX_TIME_SCALE: f32 = 8.0;
+
+/// Playback state shared by the keyboard, the parameter panel and the main loop.
+#[derive(Debug)]
+pub struct Controls {
+    pub paused: bool,
+    // Simulated seconds per real second
+    pub time_scale: f32,
+    // Draw particles between their last two positions instead of snapping
+    pub interpolate: bool,
+    // Held down rather than toggled
+    pub rewinding: bool,
+    // One-shot requests, cleared by the main loop once handled
+    pub step: bool,
+    pub substep: bool,
+    pub reset: bool,
+}
+
+impl Default for Controls {
+    fn default() -> Self {
+        Controls {
+            paused: false,
+            time_scale: 1.0,
+            interpolate: true,
+            rewinding: false,
+            step: false,
+            substep: false,
+            reset: false,
+        }
+    }
+}
+
+impl Controls {
+    /// P pauses, `.` steps a frame, `,` steps a substep, up/down double or
+    /// halve the time scale, I toggles interpolation, R resets and holding
+    /// backspace rewinds.
+    pub fn handle_keys(&mut self) {
+        if is_key_pressed(KeyCode::P) {
+            self.paused = !self.paused;
+        }
+        if is_key_pressed(KeyCode::Period) {
+            self.paused = true;
+            self.step = true;
+        }
+        if is_key_pressed(KeyCode::Comma) {
+            self.paused = true;
+            self.substep = true;
+        }
+        if is_key_pressed(KeyCode::Up) {
+            self.time_scale = (self.time_scale * 2.0).min(MAX_TIME_SCALE);
+        }
+        if is_key_pressed(KeyCode::Down) {
+            self.time_scale = (self.time_scale / 2.0).max(MIN_TIME_SCALE);
+        }
+        if is_key_pressed(KeyCode::I) {
+            self.interpolate = !self.interpolate;
+        }
+        if is_key_pressed(KeyCode::R) {
+            self.reset = true;
+        }
+        self.rewinding = is_key_down(KeyCode::Backspace);
+    }
+
+    pub fn take_step(&mut self) -> bool {
+        std::mem::take(&mut self.step)
+    }
+
+    pub fn take_substep(&mut self) -> bool {
+        std::mem::take(&mut self.substep)
+    }
+
+    pub fn take_reset(&mut self) -> bool {
+        std::mem::take(&mut self.reset)
+    }
+
+    /// Whole steps to run this frame, from the real time since the last one
+    /// scaled by the time scale. Paused, only requested single steps run.
+    pub fn steps_this_frame(&mut self, timestep: &mut FixedTimestep) -> u32 {
+        // Taken either way, so a request can't linger until the next pause
+        let step = self.take_step();
+        if self.paused {
+            return step as u32;
+        }
+
+        timestep.advance_scaled(get_frame_time(), self.time_scale)
+    }
+
+    /// How far to interpolate rendering towards the current positions.
+    pub fn render_alpha(&self, timestep: &FixedTimestep) -> f32 {
+        if self.interpolate && !self.paused {
+            timestep.alpha()
+        } else {
+            1.0
+        }
+    }
+}
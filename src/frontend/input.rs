@@ -0,0 +1,77 @@
+use macroquad::prelude::*;
+use verlet::{Particle, VerletSimulation};
+
+// How far from the cursor a particle can be grabbed
+const GRAB_RADIUS: f32 = 30.0;
+// Fraction of the gap to the cursor the grabbed particle closes per substep
+const SPRING_STIFFNESS: f32 = 0.05;
+const SPRING_DAMPING: f32 = 0.5;
+const PUSH_RADIUS: f32 = 80.0;
+const PUSH_STRENGTH: f32 = 6.0;
+const ATTRACT_STRENGTH: f32 = 0.3;
+
+/// Mouse and keyboard interaction with the running simulation:
+/// - left drag grabs the nearest particle with a spring, pinned ones follow the cursor exactly
+/// - right click kicks particles away from the cursor, shift + right hold pulls them in
+/// - holding space spawns particles at the cursor
+pub struct MouseInput {
+    // By id, removals move particles to other indices
+    grabbed: Option<u64>,
+}
+
+impl MouseInput {
+    pub fn new() -> Self {
+        MouseInput { grabbed: None }
+    }
+
+    /// Id of the grabbed particle.
+    pub fn grabbed(&self) -> Option<u64> {
+        self.grabbed
+    }
+
+    pub fn update(&mut self, simulation: &mut VerletSimulation) {
+        let mouse = Vec2::from(mouse_position());
+
+        if is_mouse_button_pressed(MouseButton::Left) {
+            self.grabbed = simulation.nearest_particle(mouse, GRAB_RADIUS).map(|i| simulation.particles.id(i));
+        }
+        if is_mouse_button_released(MouseButton::Left) {
+            self.grabbed = None;
+        }
+        // The particle may have been removed while held
+        let held = self.grabbed.and_then(|id| simulation.particles.index_of(id));
+        if held.is_none() {
+            self.grabbed = None;
+        }
+
+        if let Some(i) = held {
+            let particles = &mut simulation.particles;
+            if particles.is_pinned(i) {
+                particles.move_to(i, mouse);
+            } else {
+                // Damped spring towards the cursor
+                let pos = particles.pos(i);
+                let vel = pos - particles.old_pos(i);
+                let target = (mouse - pos) * SPRING_STIFFNESS;
+                particles.set_old_pos(i, pos - vel.lerp(target, SPRING_DAMPING));
+            }
+        }
+
+        let shift = is_key_down(KeyCode::LeftShift) || is_key_down(KeyCode::RightShift);
+        if shift && is_mouse_button_down(MouseButton::Right) {
+            simulation.push_particles(mouse, PUSH_RADIUS, -ATTRACT_STRENGTH);
+        } else if is_mouse_button_pressed(MouseButton::Right) {
+            simulation.push_particles(mouse, PUSH_RADIUS, PUSH_STRENGTH);
+        }
+
+        // Wait for the last one to move off the cursor, stacking them on one point goes nowhere
+        let config = &simulation.config;
+        if is_key_down(KeyCode::Space)
+            && simulation.particles.len() < config.max_particles
+            && simulation.nearest_particle(mouse, config.particle_radius).is_none()
+        {
+            let particle = Particle::new(mouse, mouse, config.particle_radius, config.particle_mass);
+            simulation.add_particle(particle);
+        }
+    }
+}
@@ -0,0 +1,101 @@
+use macroquad::prelude::*;
+use std::time::{Duration, Instant};
+use verlet::{FixedTimestep, History, RollingStats, SimulationConfig, VerletSimulation};
+
+mod batch;
+mod colors;
+mod controls;
+mod input;
+mod panel;
+mod render;
+
+use batch::DiscBatch;
+use colors::Coloring;
+use controls::Controls;
+use input::MouseInput;
+use panel::{draw_panel, is_mouse_over_panel};
+use render::render;
+
+const DT: f32 = 1.0 / 60.0;
+// Steps allowed per rendered frame before the simulation falls behind real time
+const MAX_STEPS_PER_FRAME: u32 = 5;
+// How far back the rewind buffer reaches, in steps
+const REWIND_FRAMES: usize = 5 * 60;
+// Steps the displayed timings are averaged over
+const STATS_WINDOW: usize = 60;
+
+#[macroquad::main("Verlet Simulation")]
+async fn main() {
+    // An optional TOML/JSON config file can be given as the first argument
+    let config = match std::env::args().nth(1) {
+        Some(path) => SimulationConfig::load(&path).unwrap_or_else(|err| {
+            eprintln!("{path}: {err}");
+            std::process::exit(1);
+        }),
+        None => SimulationConfig::default(),
+    };
+    request_new_screen_size(config.width, config.height);
+
+    let mut simulation = VerletSimulation::new(config).unwrap();
+    
+    let mut timestep = FixedTimestep::new(DT, MAX_STEPS_PER_FRAME);
+    let mut update_time: Duration;
+    let mut render_time: Duration = Duration::new(0, 0);
+    let mut mouse = MouseInput::new();
+    let mut controls = Controls::default();
+    let mut history = History::new(REWIND_FRAMES);
+    let mut stats = RollingStats::new(STATS_WINDOW);
+    let mut discs = DiscBatch::new();
+    let mut coloring = Coloring::default();
+
+    loop {
+        controls.handle_keys();
+        coloring.handle_keys();
+
+        if controls.take_reset() {
+            // Start over with the live parameters
+            match VerletSimulation::new(simulation.config.clone()) {
+                Ok(fresh) => simulation = fresh,
+                Err(err) => eprintln!("reset failed: {err}"),
+            }
+            history.clear();
+            stats.clear();
+            timestep.reset();
+        }
+
+        let mut start = Instant::now();
+        if controls.rewinding {
+            // Walk back one recorded frame per rendered frame
+            controls.paused = true;
+            history.rewind(&mut simulation);
+        } else {
+            if !is_mouse_over_panel() {
+                mouse.update(&mut simulation);
+            }
+
+            if controls.take_substep() {
+                history.record(&simulation);
+                simulation.substep(DT / simulation.sub_step_count(DT) as f32);
+            }
+
+            // Update the simulation with a fixed timestep, independent of the refresh rate
+            for _ in 0..controls.steps_this_frame(&mut timestep) {
+                history.record(&simulation);
+                stats.push(simulation.step(DT));
+            }
+        }
+
+        update_time = start.elapsed();
+        start = Instant::now();
+        
+        // Render
+        coloring.prepare(&simulation);
+        render(&simulation, &mut discs, &coloring, controls.render_alpha(&timestep), mouse.grabbed(), &format!("Update: {:.2}ms\nRender: {:.2}ms\nParticles: {}\n{}", update_time.as_secs_f64() * 1000.0, render_time.as_secs_f64() * 1000.0, simulation.particles.len(), stats.average())).unwrap();
+
+        render_time = start.elapsed();
+
+        draw_panel(&mut simulation, &mut controls, &mut coloring, history.len());
+            
+        next_frame().await
+    }
+}
\ No newline at end of file
@@ -0,0 +1,107 @@
+use macroquad::prelude::*;
+use macroquad::ui::{hash, root_ui, widgets};
+use verlet::{Substeps, VerletSimulation};
+
+use crate::colors::{ColorMode, Coloring, Palette};
+use crate::controls::{Controls, MAX_TIME_SCALE, MIN_TIME_SCALE};
+
+const PANEL_SIZE: Vec2 = Vec2::new(320.0, 450.0);
+
+pub fn is_mouse_over_panel() -> bool {
+    root_ui().is_mouse_over(Vec2::from(mouse_position()))
+}
+
+/// Draws the parameter window. Every slider writes straight into the live
+/// config, so changes apply on the next step.
+pub fn draw_panel(simulation: &mut VerletSimulation, controls: &mut Controls, coloring: &mut Coloring, history_len: usize) {
+    let position = vec2(screen_width() - PANEL_SIZE.x - 10.0, 10.0);
+
+    widgets::Window::new(hash!(), position, PANEL_SIZE)
+        .label("Parameters")
+        .ui(&mut root_ui(), |ui| {
+            let config = &mut simulation.config;
+            ui.slider(hash!(), "Gravity X", -2000.0..2000.0, &mut config.gravity.x);
+            ui.slider(hash!(), "Gravity Y", -2000.0..2000.0, &mut config.gravity.y);
+            ui.slider(hash!(), "Damping", 0.0..1.0, &mut config.damping);
+
+            let mut adaptive = matches!(config.sub_steps, Substeps::Adaptive { .. });
+            ui.checkbox(hash!(), "Adaptive substeps", &mut adaptive);
+            match (adaptive, &mut config.sub_steps) {
+                (false, Substeps::Fixed(count)) => {
+                    let mut sub_steps = *count as f32;
+                    ui.slider(hash!(), "Substeps", 1.0..16.0, &mut sub_steps);
+                    *count = (sub_steps.round() as u32).max(1);
+                }
+                (true, Substeps::Adaptive { max_travel, .. }) => {
+                    ui.slider(hash!(), "Max travel", 0.05..2.0, max_travel);
+                }
+                (false, _) => config.sub_steps = Substeps::Fixed(6),
+                (true, _) => config.sub_steps = Substeps::adaptive(),
+            }
+
+            let mut max_particles = config.max_particles as f32;
+            ui.slider(hash!(), "Max particles", 0.0..20000.0, &mut max_particles);
+            config.max_particles = max_particles.round() as usize;
+
+            // New particles only, from the emitters and the mouse
+            let old_radius = config.particle_radius;
+            ui.slider(hash!(), "Particle radius", 1.0..20.0, &mut config.particle_radius);
+            let radius_changed = config.particle_radius != old_radius;
+
+            if let Some(first) = simulation.emitters.first() {
+                let (old_interval, old_burst) = (first.interval as f32, first.burst as f32);
+                let (mut interval, mut burst) = (old_interval, old_burst);
+                ui.slider(hash!(), "Spawn interval", 1.0..30.0, &mut interval);
+                ui.slider(hash!(), "Spawn burst", 1.0..20.0, &mut burst);
+
+                // Only touch the emitters when a slider moved, so their own settings survive.
+                // Mirror into the config too so a reset keeps the change.
+                let emitters = simulation.emitters.iter_mut().chain(config.emitters.iter_mut());
+                for emitter in emitters {
+                    if interval != old_interval {
+                        emitter.interval = (interval.round() as u32).max(1);
+                    }
+                    if burst != old_burst {
+                        emitter.burst = burst.round() as u32;
+                    }
+                    if radius_changed {
+                        emitter.template.radius = config.particle_radius;
+                    }
+                }
+            }
+
+            ui.separator();
+            let modes = ColorMode::ALL.map(ColorMode::name);
+            let mut mode = ColorMode::ALL.iter().position(|&m| m == coloring.mode).unwrap_or(0);
+            ui.combo_box(hash!(), "Colour", &modes, &mut mode);
+            coloring.mode = ColorMode::ALL[mode];
+
+            let palettes = Palette::ALL.map(Palette::name);
+            let mut palette = Palette::ALL.iter().position(|&p| p == coloring.palette).unwrap_or(0);
+            ui.combo_box(hash!(), "Palette", &palettes, &mut palette);
+            coloring.palette = Palette::ALL[palette];
+
+            ui.separator();
+            ui.slider(hash!(), "Time scale", MIN_TIME_SCALE..MAX_TIME_SCALE, &mut controls.time_scale);
+            ui.checkbox(hash!(), "Paused", &mut controls.paused);
+            ui.checkbox(hash!(), "Interpolate", &mut controls.interpolate);
+            if ui.button(None, if controls.paused { "Resume" } else { "Pause" }) {
+                controls.paused = !controls.paused;
+            }
+            ui.same_line(0.0);
+            if ui.button(None, "Step") {
+                controls.paused = true;
+                controls.step = true;
+            }
+            ui.same_line(0.0);
+            if ui.button(None, "Substep") {
+                controls.paused = true;
+                controls.substep = true;
+            }
+            ui.same_line(0.0);
+            if ui.button(None, "Reset") {
+                controls.reset = true;
+            }
+            ui.label(None, &format!("Hold backspace to rewind ({history_len} frames)"));
+        });
+}
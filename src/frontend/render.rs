@@ -0,0 +1,113 @@
+use macroquad::prelude::*;
+use verlet::{Boundary, Collider, KillZone, VerletSimulation};
+
+use crate::batch::DiscBatch;
+use crate::colors::Coloring;
+
+const BACKGROUND: Color = Color::new(0.0, 0.0, 0.0, 1.0);
+
+fn draw_boundary(boundary: &Boundary) {
+    let fill = Color::from_rgba(255, 255, 255, 100);
+
+    match boundary {
+        Boundary::None => {}
+        Boundary::Circle { center, radius } => draw_circle(center.x, center.y, *radius, fill),
+        Boundary::Rect { min, max } => draw_rectangle(min.x, min.y, max.x - min.x, max.y - min.y, fill),
+        Boundary::Polygon { points } => {
+            // Triangle fan, the polygon is convex
+            for i in 1..points.len() - 1 {
+                draw_triangle(points[0], points[i], points[i + 1], fill);
+            }
+        }
+        Boundary::Annulus { center, inner_radius, outer_radius } => {
+            draw_circle(center.x, center.y, *outer_radius, fill);
+            draw_circle(center.x, center.y, *inner_radius, BACKGROUND);
+        }
+    }
+}
+
+fn draw_collider(collider: &Collider) {
+    let fill = Color::from_rgba(120, 120, 140, 255);
+
+    match collider {
+        Collider::Segment { a, b } => draw_line(a.x, a.y, b.x, b.y, 2.0, fill),
+        Collider::Capsule { a, b, radius } => {
+            draw_line(a.x, a.y, b.x, b.y, radius * 2.0, fill);
+            draw_circle(a.x, a.y, *radius, fill);
+            draw_circle(b.x, b.y, *radius, fill);
+        }
+        Collider::Polygon { points } => {
+            for i in 1..points.len() - 1 {
+                draw_triangle(points[0], points[i], points[i + 1], fill);
+            }
+        }
+        Collider::Circle { center, radius } => draw_circle(center.x, center.y, *radius, fill),
+    }
+}
+
+fn draw_kill_zone(kill_zone: &KillZone) {
+    let fill = Color::from_rgba(255, 60, 60, 80);
+
+    match kill_zone {
+        KillZone::Circle { center, radius } => draw_circle(center.x, center.y, *radius, fill),
+        KillZone::Rect { min, max } => draw_rectangle(min.x, min.y, max.x - min.x, max.y - min.y, fill),
+    }
+}
+
+pub fn render(
+    simulation: &VerletSimulation,
+    discs: &mut DiscBatch,
+    coloring: &Coloring,
+    alpha: f32,
+    grabbed: Option<u64>,
+    text: &str,
+) -> Result<(), String> {
+    // Clear the screen
+    clear_background(BACKGROUND);
+
+    // Draw the container
+    draw_boundary(&simulation.config.boundary);
+
+    // Draw the obstacles and sinks
+    for collider in &simulation.config.colliders {
+        draw_collider(collider);
+    }
+    for kill_zone in &simulation.config.kill_zones {
+        draw_kill_zone(kill_zone);
+    }
+
+    // Positions `alpha` of the way through the last step
+    let particles = &simulation.particles;
+    let draw_pos = |i: usize| particles.previous_pos(i).lerp(particles.pos(i), alpha);
+
+    // Draw links
+    for constraint in &simulation.constraints {
+        let a = draw_pos(constraint.a);
+        let b = draw_pos(constraint.b);
+        draw_line(a.x, a.y, b.x, b.y, 2.0, Color::from_rgba(150, 150, 150, 255));
+    }
+
+    // Draw particles, all in a few batched meshes
+    for i in 0..particles.len() {
+        discs.push(draw_pos(i), particles.radius(i), coloring.color(simulation, i));
+    }
+    discs.draw();
+
+    // Draw the spring to the grabbed particle
+    if let Some(i) = grabbed.and_then(|id| particles.index_of(id)) {
+        let pos = draw_pos(i);
+        let (x, y) = mouse_position();
+        draw_line(pos.x, pos.y, x, y, 1.0, Color::from_rgba(255, 220, 0, 255));
+    }
+
+    // Draw debug info, draw_text doesn't break lines itself
+    for (i, line) in text.lines().enumerate() {
+        draw_text(
+            line.trim(),
+            10.0, 20.0 + i as f32 * 18.0, 20.0, Color::from_rgba(255, 255, 255, 255)
+        );
+    }
+    coloring.draw_legend();
+
+    Ok(())
+}
@@ -0,0 +1,94 @@
+use glam::Vec2;
+use std::ops::Range;
+
+use crate::particles::Particles;
+
+// Upper bound on the cells of a grid. Beyond it cells grow wider than a
+// diameter, which stays correct and keeps tiny particles from asking for
+// billions of cells.
+const MAX_CELLS: f64 = (1 << 20) as f64;
+
+// Uniform grid used as the collision broadphase. Cells are at least one
+// particle diameter wide, so every contact lies within the 3x3 block around a cell.
+#[derive(Clone)]
+pub(crate) struct CollisionGrid {
+    // The size asked for, and the one used after the cell limit
+    requested: f32,
+    pub(crate) cell_size: f32,
+    pub(crate) cols: usize,
+    pub(crate) rows: usize,
+    cell_start: Vec<usize>,
+    cell_particles: Vec<usize>,
+}
+
+impl CollisionGrid {
+    // Half of the 3x3 stencil, so every neighbouring pair of cells is visited once
+    pub(crate) const NEIGHBOURS: [(isize, isize); 4] = [(1, 0), (-1, 1), (0, 1), (1, 1)];
+
+    pub(crate) fn new(width: f32, height: f32, requested: f32) -> Self {
+        let (width, height) = (width as f64, height as f64);
+        let cell_size = (requested as f64).max((width * height / MAX_CELLS).sqrt());
+        let cols = ((width / cell_size).ceil() as usize).max(1);
+        let rows = ((height / cell_size).ceil() as usize).max(1);
+
+        CollisionGrid {
+            requested,
+            cell_size: cell_size as f32,
+            cols,
+            rows,
+            cell_start: vec![0; cols * rows + 1],
+            cell_particles: Vec::new(),
+        }
+    }
+
+    /// Whether the grid was built for cells of `cell_size`.
+    pub(crate) fn is_sized_for(&self, cell_size: f32) -> bool {
+        self.requested == cell_size
+    }
+
+    fn cell_of(&self, pos: Vec2) -> usize {
+        // Particles outside the grid are clamped into the border cells
+        let x = ((pos.x / self.cell_size) as isize).clamp(0, self.cols as isize - 1) as usize;
+        let y = ((pos.y / self.cell_size) as isize).clamp(0, self.rows as isize - 1) as usize;
+        y * self.cols + x
+    }
+
+    // Counting sort of the particle indices by cell
+    pub(crate) fn rebuild(&mut self, particles: &Particles) {
+        let cells = self.cols * self.rows;
+
+        self.cell_start.fill(0);
+        for i in 0..particles.len() {
+            let cell = self.cell_of(particles.pos(i));
+            self.cell_start[cell] += 1;
+        }
+        for cell in 1..=cells {
+            self.cell_start[cell] += self.cell_start[cell - 1];
+        }
+
+        // Walk backwards so each cell keeps its particles in index order
+        self.cell_particles.resize(particles.len(), 0);
+        for i in (0..particles.len()).rev() {
+            let cell = self.cell_of(particles.pos(i));
+            self.cell_start[cell] -= 1;
+            self.cell_particles[self.cell_start[cell]] = i;
+        }
+    }
+
+    /// Particle indices sorted by cell. Rows are contiguous, so a band of
+    /// rows is a contiguous range of this slice.
+    pub(crate) fn order(&self) -> &[usize] {
+        &self.cell_particles
+    }
+
+    /// Range of `order()` holding the particles in cell `(x, y)`.
+    pub(crate) fn cell_range(&self, x: usize, y: usize) -> Range<usize> {
+        let cell = y * self.cols + x;
+        self.cell_start[cell]..self.cell_start[cell + 1]
+    }
+
+    /// Range of `order()` holding the particles in a band of rows.
+    pub(crate) fn rows_range(&self, rows: Range<usize>) -> Range<usize> {
+        self.cell_start[rows.start * self.cols]..self.cell_start[rows.end * self.cols]
+    }
+}
@@ -0,0 +1,159 @@
+use std::path::PathBuf;
+
+use crate::trajectory::Format;
+
+pub const USAGE: &str = "\
+Usage: headless [CONFIG] [OPTIONS]
+
+Steps the simulation without a window and writes particle trajectories.
+
+Arguments:
+  [CONFIG]              TOML or JSON config, the built-in scene if omitted
+
+Options:
+  --snapshot PATH       Start from a saved snapshot instead of a config
+  --steps N             Steps to run [default: 600]
+  --dt SECONDS          Length of a step [default: 1/60]
+  --every N             Write every Nth step [default: 1]
+  --format csv|binary   Trajectory format [default: binary for .bin outputs, csv otherwise]
+  -o, --output PATH     Write trajectories to PATH instead of stdout
+  --stats PATH          Write per-step timings, a Chrome trace for .json paths, CSV otherwise
+  -h, --help            Print this help";
+
+#[derive(Debug)]
+pub struct Args {
+    pub config: Option<PathBuf>,
+    pub snapshot: Option<PathBuf>,
+    pub steps: u64,
+    pub dt: f32,
+    pub every: u64,
+    pub format: Format,
+    pub output: Option<PathBuf>,
+    pub stats: Option<PathBuf>,
+}
+
+impl Args {
+    /// Parses the arguments after the program name. `Ok(None)` means help
+    /// was asked for.
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Option<Args>, String> {
+        let mut config = None;
+        let mut snapshot = None;
+        let mut steps = 600;
+        let mut dt: f32 = 1.0 / 60.0;
+        let mut every = 1;
+        let mut format = None;
+        let mut output: Option<PathBuf> = None;
+        let mut stats = None;
+
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or(format!("{arg} needs a value"));
+            match arg.as_str() {
+                "-h" | "--help" => return Ok(None),
+                "--snapshot" => snapshot = Some(PathBuf::from(value()?)),
+                "--steps" => steps = number(&arg, &value()?)?,
+                "--dt" => dt = number(&arg, &value()?)?,
+                "--every" => every = number(&arg, &value()?)?,
+                "--format" => {
+                    format = Some(match value()?.as_str() {
+                        "csv" => Format::Csv,
+                        "binary" => Format::Binary,
+                        other => return Err(format!("unknown format {other}, expected csv or binary")),
+                    })
+                }
+                "-o" | "--output" => output = Some(PathBuf::from(value()?)),
+                "--stats" => stats = Some(PathBuf::from(value()?)),
+                _ if arg.starts_with('-') => return Err(format!("unknown option {arg}")),
+                _ if config.is_none() => config = Some(PathBuf::from(arg)),
+                _ => return Err(format!("unexpected argument {arg}")),
+            }
+        }
+
+        if config.is_some() && snapshot.is_some() {
+            return Err("a config and a snapshot can't be used together".to_string());
+        }
+        if !(dt > 0.0 && dt.is_finite()) {
+            return Err(format!("--dt must be positive, got {dt}"));
+        }
+        if every == 0 {
+            return Err("--every must be at least 1".to_string());
+        }
+
+        let format = format.unwrap_or_else(|| {
+            match output.as_ref().and_then(|path| path.extension()).and_then(|ext| ext.to_str()) {
+                Some("bin") => Format::Binary,
+                _ => Format::Csv,
+            }
+        });
+
+        Ok(Some(Args {
+            config,
+            snapshot,
+            steps,
+            dt,
+            every,
+            format,
+            output,
+            stats,
+        }))
+    }
+}
+
+fn number<T: std::str::FromStr>(option: &str, value: &str) -> Result<T, String> {
+    value.parse().map_err(|_| format!("{option} expects a number, got {value}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Option<Args>, String> {
+        Args::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn defaults() {
+        let args = parse(&[]).unwrap().unwrap();
+        assert_eq!(args.config, None);
+        assert_eq!(args.snapshot, None);
+        assert_eq!(args.steps, 600);
+        assert_eq!(args.dt, 1.0 / 60.0);
+        assert_eq!(args.every, 1);
+        assert_eq!(args.format, Format::Csv);
+        assert_eq!(args.output, None);
+        assert_eq!(args.stats, None);
+    }
+
+    #[test]
+    fn help_wins_over_everything_else() {
+        assert!(parse(&["--help"]).unwrap().is_none());
+        assert!(parse(&["scene.toml", "--steps", "10", "-h"]).unwrap().is_none());
+    }
+
+    #[test]
+    fn format_follows_the_output_unless_given() {
+        let format = |args: &[&str]| parse(args).unwrap().unwrap().format;
+        assert_eq!(format(&["-o", "out.bin"]), Format::Binary);
+        assert_eq!(format(&["--output", "out.csv"]), Format::Csv);
+        assert_eq!(format(&["-o", "out.bin", "--format", "csv"]), Format::Csv);
+        assert_eq!(format(&["--format", "binary"]), Format::Binary);
+    }
+
+    #[test]
+    fn rejects_bad_combinations_and_values() {
+        for args in [
+            &["scene.toml", "--snapshot", "saved.bin"][..],
+            &["--every", "0"],
+            &["--dt", "0"],
+            &["--dt", "-0.1"],
+            &["--dt", "inf"],
+            &["--dt", "fast"],
+            &["--steps"],
+            &["--format", "xml"],
+            &["--verbose"],
+            &["one.toml", "two.toml"],
+        ] {
+            assert!(parse(args).is_err(), "{args:?}");
+        }
+    }
+}
@@ -0,0 +1,76 @@
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::process::ExitCode;
+use std::time::Instant;
+use verlet::{SimulationConfig, StatsLog, VerletSimulation};
+
+mod args;
+mod trajectory;
+
+use args::{Args, USAGE};
+use trajectory::TrajectoryWriter;
+
+fn main() -> ExitCode {
+    let args = match Args::parse(std::env::args().skip(1)) {
+        Ok(Some(args)) => args,
+        Ok(None) => {
+            println!("{USAGE}");
+            return ExitCode::SUCCESS;
+        }
+        Err(err) => {
+            eprintln!("{err}\n\n{USAGE}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match run(&args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("{err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(args: &Args) -> Result<(), String> {
+    let mut simulation = if let Some(path) = &args.snapshot {
+        VerletSimulation::load(path).map_err(|err| format!("{}: {err}", path.display()))?
+    } else {
+        let config = match &args.config {
+            Some(path) => SimulationConfig::load(path).map_err(|err| format!("{}: {err}", path.display()))?,
+            None => SimulationConfig::default(),
+        };
+        VerletSimulation::new(config).map_err(|err| err.to_string())?
+    };
+
+    let out: Box<dyn Write> = match &args.output {
+        Some(path) => Box::new(File::create(path).map_err(|err| format!("{}: {err}", path.display()))?),
+        None => Box::new(io::stdout().lock()),
+    };
+    let write_error = |err: io::Error| format!("could not write trajectories: {err}");
+    let mut writer = TrajectoryWriter::new(BufWriter::new(out), args.format).map_err(write_error)?;
+
+    let mut stats = StatsLog::new();
+    let start = Instant::now();
+    writer.write_frame(simulation.frame(), &simulation.particles).map_err(write_error)?;
+    for step in 1..=args.steps {
+        stats.record(simulation.step(args.dt));
+        if step.is_multiple_of(args.every) {
+            writer.write_frame(simulation.frame(), &simulation.particles).map_err(write_error)?;
+        }
+    }
+    writer.finish().map_err(write_error)?;
+    if let Some(path) = &args.stats {
+        stats.save(path).map_err(|err| format!("{}: {err}", path.display()))?;
+    }
+
+    let elapsed = start.elapsed();
+    eprintln!(
+        "{} steps in {:.2}s ({:.0} steps/s), {} particles at the end",
+        args.steps,
+        elapsed.as_secs_f64(),
+        args.steps as f64 / elapsed.as_secs_f64(),
+        simulation.particles.len()
+    );
+    Ok(())
+}
@@ -0,0 +1,117 @@
+use std::io::{self, Write};
+use verlet::Particles;
+
+// Binary layout, all little endian:
+//   magic "VTRJ", version u32,
+//   then per written frame: frame u64, particle count u64,
+//     every particle id as u64, then every x as f32, then every y as f32.
+// The particle count can change between frames as particles spawn and die.
+// Ids stay with their particle, rows don't.
+const MAGIC: &[u8; 4] = b"VTRJ";
+const VERSION: u32 = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    // One `frame,particle,x,y` row per particle per frame, keyed by particle id
+    Csv,
+    Binary,
+}
+
+pub struct TrajectoryWriter<W: Write> {
+    out: W,
+    format: Format,
+}
+
+impl<W: Write> TrajectoryWriter<W> {
+    pub fn new(mut out: W, format: Format) -> io::Result<Self> {
+        match format {
+            Format::Csv => writeln!(out, "frame,particle,x,y")?,
+            Format::Binary => {
+                out.write_all(MAGIC)?;
+                out.write_all(&VERSION.to_le_bytes())?;
+            }
+        }
+        Ok(TrajectoryWriter { out, format })
+    }
+
+    pub fn write_frame(&mut self, frame: u64, particles: &Particles) -> io::Result<()> {
+        let (xs, ys) = particles.positions();
+        let ids = particles.ids();
+        match self.format {
+            Format::Csv => {
+                for ((id, x), y) in ids.iter().zip(xs).zip(ys) {
+                    writeln!(self.out, "{frame},{id},{x},{y}")?;
+                }
+            }
+            Format::Binary => {
+                self.out.write_all(&frame.to_le_bytes())?;
+                self.out.write_all(&(xs.len() as u64).to_le_bytes())?;
+                for id in ids {
+                    self.out.write_all(&id.to_le_bytes())?;
+                }
+                for value in xs.iter().chain(ys) {
+                    self.out.write_all(&value.to_le_bytes())?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn finish(mut self) -> io::Result<()> {
+        self.out.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use verlet::{Particle, SimulationConfig, Vec2, VerletSimulation};
+
+    // Three particles with the first removed, so ids and rows differ
+    fn particles() -> Particles {
+        let config = SimulationConfig { emitters: Vec::new(), ..SimulationConfig::default() };
+        let mut simulation = VerletSimulation::new(config).unwrap();
+        for x in [1.0, 2.5, -4.0] {
+            let pos = Vec2::new(x, x * 10.0);
+            simulation.add_particle(Particle::new(pos, pos, 1.0, 1.0));
+        }
+        simulation.remove_particle(0);
+        simulation.particles
+    }
+
+    fn write(format: Format) -> Vec<u8> {
+        let particles = particles();
+        let mut out = Vec::new();
+        let mut writer = TrajectoryWriter::new(&mut out, format).unwrap();
+        writer.write_frame(3, &particles).unwrap();
+        writer.write_frame(4, &Particles::new()).unwrap();
+        writer.finish().unwrap();
+        out
+    }
+
+    #[test]
+    fn csv_has_a_row_per_particle_keyed_by_id() {
+        let csv = String::from_utf8(write(Format::Csv)).unwrap();
+        assert_eq!(csv, "frame,particle,x,y\n3,2,-4,-40\n3,1,2.5,25\n");
+    }
+
+    #[test]
+    fn binary_has_ids_then_xs_then_ys_per_frame() {
+        let mut expected = Vec::new();
+        expected.extend_from_slice(b"VTRJ");
+        expected.extend_from_slice(&2u32.to_le_bytes());
+        expected.extend_from_slice(&3u64.to_le_bytes());
+        expected.extend_from_slice(&2u64.to_le_bytes());
+        for id in [2u64, 1] {
+            expected.extend_from_slice(&id.to_le_bytes());
+        }
+        for value in [-4.0f32, 2.5, -40.0, 25.0] {
+            expected.extend_from_slice(&value.to_le_bytes());
+        }
+        // An empty frame is just its header
+        expected.extend_from_slice(&4u64.to_le_bytes());
+        expected.extend_from_slice(&0u64.to_le_bytes());
+
+        assert_eq!(write(Format::Binary), expected);
+    }
+}
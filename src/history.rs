@@ -0,0 +1,85 @@
+use std::collections::VecDeque;
+
+use crate::config::SimulationConfig;
+use crate::constraint::DistanceConstraint;
+use crate::emitter::Emitter;
+use crate::particles::Particles;
+use crate::rng::Rng;
+use crate::simulation::VerletSimulation;
+
+// Everything a step changes, without the solver's scratch buffers
+struct State {
+    config: SimulationConfig,
+    // Without the derived arrays, they are rebuilt on rewind
+    particles: Particles,
+    constraints: Vec<DistanceConstraint>,
+    emitters: Vec<Emitter>,
+    frame: u64,
+    rng: Rng,
+    last_sub_dt: Option<f32>,
+}
+
+/// Bounded ring buffer of past simulation states for rewinding. Once full,
+/// recording a state drops the oldest one. Contact counts and pressure are
+/// not kept and read zero after a rewind until the next step.
+pub struct History {
+    states: VecDeque<State>,
+    capacity: usize,
+}
+
+impl History {
+    pub fn new(capacity: usize) -> Self {
+        History {
+            states: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    pub fn record(&mut self, simulation: &VerletSimulation) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.states.len() == self.capacity {
+            self.states.pop_front();
+        }
+        self.states.push_back(State {
+            config: simulation.config.clone(),
+            particles: simulation.particles.without_derived(),
+            constraints: simulation.constraints.clone(),
+            emitters: simulation.emitters.clone(),
+            frame: simulation.frame,
+            rng: simulation.rng.clone(),
+            last_sub_dt: simulation.last_sub_dt,
+        });
+    }
+
+    /// Puts `simulation` back into the most recently recorded state and drops
+    /// it from the buffer. Returns false if there is nothing left to rewind.
+    pub fn rewind(&mut self, simulation: &mut VerletSimulation) -> bool {
+        let Some(state) = self.states.pop_back() else {
+            return false;
+        };
+
+        simulation.config = state.config;
+        simulation.particles = state.particles;
+        simulation.particles.restore_derived();
+        simulation.constraints = state.constraints;
+        simulation.emitters = state.emitters;
+        simulation.frame = state.frame;
+        simulation.rng = state.rng;
+        simulation.last_sub_dt = state.last_sub_dt;
+        true
+    }
+
+    pub fn len(&self) -> usize {
+        self.states.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.states.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.states.clear();
+    }
+}
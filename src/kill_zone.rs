@@ -0,0 +1,32 @@
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+
+/// Region that deletes every particle whose center enters it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum KillZone {
+    Circle { center: Vec2, radius: f32 },
+    Rect { min: Vec2, max: Vec2 },
+}
+
+impl KillZone {
+    pub fn contains(&self, pos: Vec2) -> bool {
+        match self {
+            KillZone::Circle { center, radius } => pos.distance_squared(*center) < radius * radius,
+            KillZone::Rect { min, max } => pos.cmpge(*min).all() && pos.cmple(*max).all(),
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        let ok = match self {
+            KillZone::Circle { center, radius } => center.is_finite() && *radius > 0.0,
+            KillZone::Rect { min, max } => min.is_finite() && max.is_finite() && max.cmpgt(*min).all(),
+        };
+
+        if ok {
+            Ok(())
+        } else {
+            Err(format!("degenerate kill zone: {self:?}"))
+        }
+    }
+}
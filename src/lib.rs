@@ -0,0 +1,37 @@
+//! Headless Verlet integration core. Nothing in here depends on a window, so
+//! simulations can be stepped from tools, tests and servers.
+
+#![forbid(unsafe_code)]
+
+mod boundary;
+mod collider;
+mod config;
+mod constraint;
+mod emitter;
+mod grid;
+mod history;
+mod kill_zone;
+mod particle;
+mod particles;
+mod rng;
+mod simulation;
+mod snapshot;
+mod solver;
+mod stats;
+mod timestep;
+
+pub use glam::Vec2;
+pub use boundary::Boundary;
+pub use collider::Collider;
+pub use config::{ConfigError, SimulationConfig, Substeps};
+pub use constraint::DistanceConstraint;
+pub use emitter::{Emitter, ParticleTemplate, SprayPattern};
+pub use history::History;
+pub use kill_zone::KillZone;
+pub use particle::Particle;
+pub use particles::{ParticleIter, Particles};
+pub use rng::Rng;
+pub use simulation::VerletSimulation;
+pub use snapshot::SnapshotError;
+pub use stats::{RollingStats, StatsLog, StepStats};
+pub use timestep::FixedTimestep;
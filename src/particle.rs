@@ -0,0 +1,82 @@
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Particle {
+    pub pos: Vec2,
+    pub old_pos: Vec2,
+    pub acceleration: Vec2,
+    pub radius: f32,
+    pub inverse_mass: f32,
+    // Frames since spawning, the particle is removed once it reaches its lifetime
+    pub age: u32,
+    pub lifetime: Option<u32>,
+    // RGBA shown by the frontend's user colour mode
+    pub color: Option<[u8; 4]>,
+    // Set by `Particles::push`, see `Particles::id`
+    pub id: u64,
+}
+
+impl Particle {
+    pub fn new(pos: Vec2, old_pos: Vec2, radius: f32, mass: f32) -> Self {
+        Particle {
+            pos,
+            old_pos,
+            acceleration: Vec2::ZERO,
+            radius,
+            inverse_mass: 1.0 / mass,
+            age: 0,
+            lifetime: None,
+            color: None,
+            id: 0,
+        }
+    }
+
+    /// A particle with infinite mass. It is never integrated or pushed, but
+    /// still pushes the dynamic particles it touches.
+    pub fn pinned(pos: Vec2, radius: f32) -> Self {
+        Particle {
+            pos,
+            old_pos: pos,
+            acceleration: Vec2::ZERO,
+            radius,
+            inverse_mass: 0.0,
+            age: 0,
+            lifetime: None,
+            color: None,
+            id: 0,
+        }
+    }
+
+    pub fn is_expired(&self) -> bool {
+        is_expired(self.age, self.lifetime)
+    }
+
+    pub fn mass(&self) -> f32 {
+        1.0 / self.inverse_mass
+    }
+
+    pub fn is_pinned(&self) -> bool {
+        self.inverse_mass == 0.0
+    }
+
+    pub fn pin(&mut self) {
+        self.inverse_mass = 0.0;
+        self.old_pos = self.pos;
+    }
+
+    pub fn unpin(&mut self, mass: f32) {
+        self.inverse_mass = 1.0 / mass;
+    }
+
+    // Moves a pinned particle along a scripted path, the move is its velocity until the next substep
+    pub fn move_to(&mut self, pos: Vec2) {
+        self.old_pos = self.pos;
+        self.pos = pos;
+    }
+}
+
+// Shared with the bulk pass in `VerletSimulation::step`
+pub(crate) fn is_expired(age: u32, lifetime: Option<u32>) -> bool {
+    lifetime.is_some_and(|lifetime| age >= lifetime)
+}
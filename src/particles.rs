@@ -0,0 +1,360 @@
+use glam::Vec2;
+use std::ops::Range;
+
+use crate::particle::Particle;
+
+/// Every particle of a simulation, stored as one array per component so the
+/// passes over all particles run on plain contiguous floats and vectorize.
+/// [`Particle`] is the by-value view of a single entry.
+#[derive(Debug, Clone, Default)]
+pub struct Particles {
+    pub(crate) x: Vec<f32>,
+    pub(crate) y: Vec<f32>,
+    pub(crate) old_x: Vec<f32>,
+    pub(crate) old_y: Vec<f32>,
+    // Positions at the start of the last step, for drawing between steps
+    pub(crate) prev_x: Vec<f32>,
+    pub(crate) prev_y: Vec<f32>,
+    pub(crate) ax: Vec<f32>,
+    pub(crate) ay: Vec<f32>,
+    pub(crate) radius: Vec<f32>,
+    pub(crate) inverse_mass: Vec<f32>,
+    pub(crate) age: Vec<u32>,
+    pub(crate) lifetime: Vec<Option<u32>>,
+    pub(crate) color: Vec<Option<[u8; 4]>>,
+    // Written by the contact solver, see `contacts` and `pressure`
+    pub(crate) contacts: Vec<u32>,
+    pub(crate) pressure: Vec<f32>,
+    pub(crate) id: Vec<u64>,
+    // Handed to the next pushed particle, ids are never reused
+    pub(crate) next_id: u64,
+}
+
+impl Particles {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.x.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.x.is_empty()
+    }
+
+    /// Adds a particle under a fresh id, whatever id the particle carries.
+    pub fn push(&mut self, mut particle: Particle) {
+        particle.id = self.next_id;
+        self.push_with_id(particle);
+    }
+
+    // Keeps the particle's id, for restoring saved particles
+    pub(crate) fn push_with_id(&mut self, particle: Particle) {
+        self.next_id = self.next_id.max(particle.id.saturating_add(1));
+        self.x.push(particle.pos.x);
+        self.y.push(particle.pos.y);
+        self.old_x.push(particle.old_pos.x);
+        self.old_y.push(particle.old_pos.y);
+        self.prev_x.push(particle.pos.x);
+        self.prev_y.push(particle.pos.y);
+        self.ax.push(particle.acceleration.x);
+        self.ay.push(particle.acceleration.y);
+        self.radius.push(particle.radius);
+        self.inverse_mass.push(particle.inverse_mass);
+        self.age.push(particle.age);
+        self.lifetime.push(particle.lifetime);
+        self.color.push(particle.color);
+        self.contacts.push(0);
+        self.pressure.push(0.0);
+        self.id.push(particle.id);
+    }
+
+    /// Copy of the particle at `index`.
+    pub fn get(&self, index: usize) -> Option<Particle> {
+        (index < self.len()).then(|| Particle {
+            pos: self.pos(index),
+            old_pos: self.old_pos(index),
+            acceleration: Vec2::new(self.ax[index], self.ay[index]),
+            radius: self.radius[index],
+            inverse_mass: self.inverse_mass[index],
+            age: self.age[index],
+            lifetime: self.lifetime[index],
+            color: self.color[index],
+            id: self.id[index],
+        })
+    }
+
+    /// Overwrites the particle at `index`, usually with an edited copy from
+    /// [`Particles::get`]. The id stays the same.
+    pub fn set(&mut self, index: usize, particle: Particle) {
+        self.set_pos(index, particle.pos);
+        self.set_old_pos(index, particle.old_pos);
+        self.ax[index] = particle.acceleration.x;
+        self.ay[index] = particle.acceleration.y;
+        self.radius[index] = particle.radius;
+        self.inverse_mass[index] = particle.inverse_mass;
+        self.age[index] = particle.age;
+        self.lifetime[index] = particle.lifetime;
+        self.color[index] = particle.color;
+    }
+
+    /// Removes a particle by moving the last one into its slot.
+    pub(crate) fn swap_remove(&mut self, index: usize) -> Particle {
+        let removed = self.get(index).expect("index in bounds");
+        self.x.swap_remove(index);
+        self.y.swap_remove(index);
+        self.old_x.swap_remove(index);
+        self.old_y.swap_remove(index);
+        self.prev_x.swap_remove(index);
+        self.prev_y.swap_remove(index);
+        self.ax.swap_remove(index);
+        self.ay.swap_remove(index);
+        self.radius.swap_remove(index);
+        self.inverse_mass.swap_remove(index);
+        self.age.swap_remove(index);
+        self.lifetime.swap_remove(index);
+        self.color.swap_remove(index);
+        self.contacts.swap_remove(index);
+        self.pressure.swap_remove(index);
+        self.id.swap_remove(index);
+        removed
+    }
+
+    pub(crate) fn clear(&mut self) {
+        let next_id = self.next_id;
+        *self = Self::default();
+        self.next_id = next_id;
+    }
+
+    /// Copies of every particle in index order.
+    pub fn iter(&self) -> ParticleIter<'_> {
+        ParticleIter {
+            particles: self,
+            indices: 0..self.len(),
+        }
+    }
+
+    pub fn pos(&self, index: usize) -> Vec2 {
+        Vec2::new(self.x[index], self.y[index])
+    }
+
+    pub fn old_pos(&self, index: usize) -> Vec2 {
+        Vec2::new(self.old_x[index], self.old_y[index])
+    }
+
+    /// Position at the start of the last step. Rendering between steps
+    /// interpolates from here to [`Particles::pos`].
+    pub fn previous_pos(&self, index: usize) -> Vec2 {
+        Vec2::new(self.prev_x[index], self.prev_y[index])
+    }
+
+    pub fn set_pos(&mut self, index: usize, pos: Vec2) {
+        self.x[index] = pos.x;
+        self.y[index] = pos.y;
+    }
+
+    pub fn set_old_pos(&mut self, index: usize, old_pos: Vec2) {
+        self.old_x[index] = old_pos.x;
+        self.old_y[index] = old_pos.y;
+    }
+
+    /// Moves a pinned particle along a scripted path. The move shows as its
+    /// velocity until the next substep, which brings it to rest again.
+    pub fn move_to(&mut self, index: usize, pos: Vec2) {
+        self.set_old_pos(index, self.pos(index));
+        self.set_pos(index, pos);
+    }
+
+    /// Identifies a particle for as long as the simulation runs. Unlike the
+    /// index it doesn't change when other particles are removed.
+    pub fn id(&self, index: usize) -> u64 {
+        self.id[index]
+    }
+
+    pub fn radius(&self, index: usize) -> f32 {
+        self.radius[index]
+    }
+
+    pub fn inverse_mass(&self, index: usize) -> f32 {
+        self.inverse_mass[index]
+    }
+
+    pub fn is_pinned(&self, index: usize) -> bool {
+        self.inverse_mass[index] == 0.0
+    }
+
+    pub fn color(&self, index: usize) -> Option<[u8; 4]> {
+        self.color[index]
+    }
+
+    /// Particles this one overlapped in the last collision pass.
+    pub fn contacts(&self, index: usize) -> u32 {
+        self.contacts[index]
+    }
+
+    /// Summed overlap depth with its neighbours in the last collision pass,
+    /// a stand-in for the contact force. Force chains in a pile show up as
+    /// lines of high pressure.
+    pub fn pressure(&self, index: usize) -> f32 {
+        self.pressure[index]
+    }
+
+    /// The x and y coordinates of every particle, for reading many at once.
+    pub fn positions(&self) -> (&[f32], &[f32]) {
+        (&self.x, &self.y)
+    }
+
+    /// Current index of the particle with `id`, if it is still alive.
+    pub fn index_of(&self, id: u64) -> Option<usize> {
+        self.id.iter().position(|&other| other == id)
+    }
+
+    /// The id of every particle, in the same order as [`Particles::positions`].
+    pub fn ids(&self) -> &[u64] {
+        &self.id
+    }
+
+    // Copy without what a step recomputes: the previous positions, the
+    // accelerations, which are zero between steps, and the solver outputs
+    pub(crate) fn without_derived(&self) -> Particles {
+        Particles {
+            x: self.x.clone(),
+            y: self.y.clone(),
+            old_x: self.old_x.clone(),
+            old_y: self.old_y.clone(),
+            radius: self.radius.clone(),
+            inverse_mass: self.inverse_mass.clone(),
+            age: self.age.clone(),
+            lifetime: self.lifetime.clone(),
+            color: self.color.clone(),
+            id: self.id.clone(),
+            next_id: self.next_id,
+            ..Particles::default()
+        }
+    }
+
+    // Refills the arrays `without_derived` left out
+    pub(crate) fn restore_derived(&mut self) {
+        let n = self.len();
+        self.prev_x.clone_from(&self.x);
+        self.prev_y.clone_from(&self.y);
+        for values in [&mut self.ax, &mut self.ay, &mut self.pressure] {
+            values.clear();
+            values.resize(n, 0.0);
+        }
+        self.contacts.clear();
+        self.contacts.resize(n, 0);
+    }
+
+    pub(crate) fn save_previous_positions(&mut self) {
+        self.prev_x.copy_from_slice(&self.x);
+        self.prev_y.copy_from_slice(&self.y);
+    }
+
+    /// Adds `acc` to the acceleration of every particle.
+    pub fn accelerate(&mut self, acc: Vec2) {
+        for ax in &mut self.ax {
+            *ax += acc.x;
+        }
+        for ay in &mut self.ay {
+            *ay += acc.y;
+        }
+    }
+
+    /// Advances every particle by one position Verlet step of `dt` and
+    /// clears the accelerations.
+    pub fn update(&mut self, dt: f32) {
+        integrate(&mut self.x, &mut self.old_x, &mut self.ax, &self.inverse_mass, dt);
+        integrate(&mut self.y, &mut self.old_y, &mut self.ay, &self.inverse_mass, dt);
+    }
+
+    // Rescales the velocity of every dynamic particle, which is stored as the
+    // distance moved in one substep
+    pub(crate) fn scale_velocities(&mut self, scale: f32) {
+        scale_axis(&self.x, &mut self.old_x, &self.inverse_mass, scale);
+        scale_axis(&self.y, &mut self.old_y, &self.inverse_mass, scale);
+    }
+}
+
+// One axis of the position Verlet step. Pinned particles keep their position,
+// drop their acceleration and come to rest after a scripted move, written as
+// selects so the loop vectorizes.
+fn integrate(pos: &mut [f32], old: &mut [f32], acc: &mut [f32], inverse_mass: &[f32], dt: f32) {
+    let n = pos.len();
+    let (old, acc, inverse_mass) = (&mut old[..n], &mut acc[..n], &inverse_mass[..n]);
+
+    for i in 0..n {
+        let free = inverse_mass[i] != 0.0;
+        let (p, o) = (pos[i], old[i]);
+        pos[i] = if free { p + ((p - o) + acc[i] * dt * dt) } else { p };
+        old[i] = p;
+        acc[i] = 0.0;
+    }
+}
+
+fn scale_axis(pos: &[f32], old: &mut [f32], inverse_mass: &[f32], scale: f32) {
+    let n = pos.len();
+    let (old, inverse_mass) = (&mut old[..n], &inverse_mass[..n]);
+
+    for i in 0..n {
+        let free = inverse_mass[i] != 0.0;
+        old[i] = if free { pos[i] - (pos[i] - old[i]) * scale } else { old[i] };
+    }
+}
+
+impl From<Vec<Particle>> for Particles {
+    fn from(particles: Vec<Particle>) -> Self {
+        particles.into_iter().collect()
+    }
+}
+
+impl From<&Particles> for Vec<Particle> {
+    fn from(particles: &Particles) -> Self {
+        particles.iter().collect()
+    }
+}
+
+impl FromIterator<Particle> for Particles {
+    fn from_iter<I: IntoIterator<Item = Particle>>(iter: I) -> Self {
+        let mut particles = Particles::new();
+        particles.extend(iter);
+        particles
+    }
+}
+
+impl Extend<Particle> for Particles {
+    fn extend<I: IntoIterator<Item = Particle>>(&mut self, iter: I) {
+        for particle in iter {
+            self.push(particle);
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a Particles {
+    type Item = Particle;
+    type IntoIter = ParticleIter<'a>;
+
+    fn into_iter(self) -> ParticleIter<'a> {
+        self.iter()
+    }
+}
+
+pub struct ParticleIter<'a> {
+    particles: &'a Particles,
+    indices: Range<usize>,
+}
+
+impl Iterator for ParticleIter<'_> {
+    type Item = Particle;
+
+    fn next(&mut self) -> Option<Particle> {
+        self.indices.next().and_then(|i| self.particles.get(i))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.indices.size_hint()
+    }
+}
+
+impl ExactSizeIterator for ParticleIter<'_> {}
@@ -0,0 +1,441 @@
+use glam::Vec2;
+use std::ops::Range;
+use std::time::Instant;
+
+use crate::config::{ConfigError, SimulationConfig, Substeps};
+use crate::constraint::DistanceConstraint;
+use crate::emitter::Emitter;
+use crate::grid::CollisionGrid;
+use crate::particle::{self, Particle};
+use crate::particles::Particles;
+use crate::rng::Rng;
+use crate::solver::{self, Body, PairCounts};
+use crate::stats::StepStats;
+
+fn reflect_vec2(vec: Vec2, normal: Vec2) -> Vec2 {
+    vec - 2.0 * vec.dot(normal) * normal
+}
+
+#[derive(Clone)]
+pub struct VerletSimulation {
+    pub config: SimulationConfig,
+    pub particles: Particles,
+    pub constraints: Vec<DistanceConstraint>,
+    pub emitters: Vec<Emitter>,
+    pub(crate) frame: u64,
+    pub(crate) rng: Rng,
+    // Substep length of the previous step, velocities are stored per substep
+    pub(crate) last_sub_dt: Option<f32>,
+    grid: CollisionGrid,
+    bodies: Vec<Body>,
+    // Scratch flags for the particles that may touch the boundary
+    near_walls: Vec<bool>,
+}
+
+impl VerletSimulation {
+    pub fn new(config: SimulationConfig) -> Result<Self, ConfigError> {
+        config.validate()?;
+
+        let particles = Particles::new();
+        let grid = CollisionGrid::new(config.width, config.height, config.particle_radius * 2.0);
+        let rng = Rng::new(config.seed);
+        let emitters = config.emitters.clone();
+
+        Ok(VerletSimulation {
+            config,
+            particles,
+            constraints: Vec::new(),
+            emitters,
+            frame: 0,
+            rng,
+            last_sub_dt: None,
+            grid,
+            bodies: Vec::new(),
+            near_walls: Vec::new(),
+        })
+    }
+
+    /// Number of steps taken so far.
+    pub fn frame(&self) -> u64 {
+        self.frame
+    }
+
+    /// The simulation's seeded random stream. Anything that spawns randomized
+    /// particles should draw from it to keep runs reproducible.
+    pub fn rng(&mut self) -> &mut Rng {
+        &mut self.rng
+    }
+
+    pub fn add_particle(&mut self, particle: Particle) -> usize {
+        self.particles.push(particle);
+        self.particles.len() - 1
+    }
+
+    // Adds a particle at rest with the default radius and mass
+    fn add_resting_particle(&mut self, pos: Vec2) -> usize {
+        let SimulationConfig { particle_radius, particle_mass, .. } = self.config;
+        self.add_particle(Particle::new(pos, pos, particle_radius, particle_mass))
+    }
+
+    fn link(&mut self, a: usize, b: usize, stiffness: f32) {
+        let rest_length = self.particles.pos(a).distance(self.particles.pos(b));
+        self.constraints.push(DistanceConstraint::new(a, b, rest_length, stiffness));
+    }
+
+    /// Builds a chain of `segments` links from `start` to `end` and returns the
+    /// indices of its particles. Spacing below a particle diameter makes the
+    /// links fight the contact response. Adds nothing for zero segments.
+    pub fn add_rope(&mut self, start: Vec2, end: Vec2, segments: usize, stiffness: f32) -> Range<usize> {
+        let first = self.particles.len();
+        if segments == 0 {
+            return first..first;
+        }
+        for i in 0..=segments {
+            self.add_resting_particle(start.lerp(end, i as f32 / segments as f32));
+            if i > 0 {
+                self.link(first + i - 1, first + i, stiffness);
+            }
+        }
+
+        first..self.particles.len()
+    }
+
+    /// Builds a `columns` x `rows` grid of particles hanging down from
+    /// `top_left`, linked to their horizontal and vertical neighbours.
+    pub fn add_cloth(
+        &mut self,
+        top_left: Vec2,
+        columns: usize,
+        rows: usize,
+        spacing: f32,
+        stiffness: f32,
+    ) -> Range<usize> {
+        let first = self.particles.len();
+        for y in 0..rows {
+            for x in 0..columns {
+                let i = self.add_resting_particle(top_left + Vec2::new(x as f32, y as f32) * spacing);
+                if x > 0 {
+                    self.link(i - 1, i, stiffness);
+                }
+                if y > 0 {
+                    self.link(i - columns, i, stiffness);
+                }
+            }
+        }
+
+        first..self.particles.len()
+    }
+
+    /// Builds a ring of `segments` particles. Besides the links around the rim
+    /// every particle is braced to the opposite one so the ring keeps its shape.
+    /// Adds nothing for fewer than three segments.
+    pub fn add_ring(&mut self, center: Vec2, radius: f32, segments: usize, stiffness: f32) -> Range<usize> {
+        let first = self.particles.len();
+        if segments < 3 {
+            return first..first;
+        }
+        for i in 0..segments {
+            let angle = i as f32 / segments as f32 * std::f32::consts::TAU;
+            self.add_resting_particle(center + Vec2::from_angle(angle) * radius);
+        }
+
+        for i in 0..segments {
+            self.link(first + i, first + (i + 1) % segments, stiffness);
+            if i < segments / 2 {
+                self.link(first + i, first + i + segments / 2, stiffness);
+            }
+        }
+
+        first..self.particles.len()
+    }
+
+    /// Adds an emitter and returns its index, or why it is invalid.
+    pub fn add_emitter(&mut self, emitter: Emitter) -> Result<usize, String> {
+        emitter.validate()?;
+        self.emitters.push(emitter);
+        Ok(self.emitters.len() - 1)
+    }
+
+    /// Removes a particle in O(1) by moving the last particle into its slot.
+    /// Links to the removed particle are dropped and links to the moved one
+    /// are renumbered, which costs a pass over the constraints if there are any.
+    pub fn remove_particle(&mut self, index: usize) -> Particle {
+        let removed = self.particles.swap_remove(index);
+
+        if !self.constraints.is_empty() {
+            let moved = self.particles.len();
+            self.constraints.retain(|c| c.a != index && c.b != index);
+            for constraint in &mut self.constraints {
+                if constraint.a == moved {
+                    constraint.a = index;
+                }
+                if constraint.b == moved {
+                    constraint.b = index;
+                }
+            }
+        }
+
+        removed
+    }
+
+    // Ages every particle by a frame and removes the expired ones and the ones in a kill zone
+    fn remove_dead_particles(&mut self) {
+        for age in &mut self.particles.age {
+            *age = age.saturating_add(1);
+        }
+
+        let mut i = 0;
+        while i < self.particles.len() {
+            let expired = particle::is_expired(self.particles.age[i], self.particles.lifetime[i]);
+            let pos = self.particles.pos(i);
+            if expired || self.config.kill_zones.iter().any(|zone| zone.contains(pos)) {
+                // The last particle moves into this slot, so check it next
+                self.remove_particle(i);
+            } else {
+                i += 1;
+            }
+        }
+    }
+
+    /// Removes every particle and the links between them. Ids keep counting
+    /// up from where they were.
+    pub fn clear_particles(&mut self) {
+        self.particles.clear();
+        self.constraints.clear();
+    }
+
+    pub fn remove_emitter(&mut self, index: usize) -> Emitter {
+        self.emitters.remove(index)
+    }
+
+    fn emit(&mut self, sub_dt: f32) {
+        for emitter in &mut self.emitters {
+            emitter.emit(&mut self.particles, self.config.max_particles, sub_dt, &mut self.rng);
+        }
+        self.emitters.retain(|emitter| !emitter.is_expired());
+    }
+
+    /// Index of the particle whose center is closest to `pos`, if any is
+    /// within `max_dist`.
+    pub fn nearest_particle(&self, pos: Vec2, max_dist: f32) -> Option<usize> {
+        (0..self.particles.len())
+            .map(|i| (i, self.particles.pos(i).distance_squared(pos)))
+            .filter(|&(_, dist_sq)| dist_sq <= max_dist * max_dist)
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(i, _)| i)
+    }
+
+    /// Velocity of a particle in units per second, zero before the first step.
+    pub fn velocity(&self, index: usize) -> Vec2 {
+        match self.last_sub_dt {
+            Some(sub_dt) => (self.particles.pos(index) - self.particles.old_pos(index)) / sub_dt,
+            None => Vec2::ZERO,
+        }
+    }
+
+    /// Kicks every particle within `radius` of `center` away from it, fading
+    /// out towards the edge. A negative `strength` pulls them in instead.
+    pub fn push_particles(&mut self, center: Vec2, radius: f32, strength: f32) {
+        for i in 0..self.particles.len() {
+            let to_obj = self.particles.pos(i) - center;
+            let dist = to_obj.length();
+            if dist < radius && dist > 0.0 && !self.particles.is_pinned(i) {
+                let old_pos = self.particles.old_pos(i) - to_obj / dist * strength * (1.0 - dist / radius);
+                self.particles.set_old_pos(i, old_pos);
+            }
+        }
+    }
+
+    /// Advances the simulation by `dt`. Given the same config, seed and calls,
+    /// the particle state is bit-identical between runs on the same platform.
+    /// Only the timings in the returned stats depend on the wall clock.
+    pub fn step(&mut self, dt: f32) -> StepStats {
+        self.frame += 1;
+
+        let sub_runs = self.sub_step_count(dt);
+        let sub_dt = dt / sub_runs as f32;
+        self.set_sub_dt(sub_dt);
+
+        self.emit(sub_dt);
+        self.particles.save_previous_positions();
+
+        let mut stats = StepStats {
+            frame: self.frame,
+            substeps: sub_runs,
+            ..StepStats::default()
+        };
+        for _ in 0..sub_runs {
+            self.run_substep(sub_dt, &mut stats);
+        }
+
+        self.remove_dead_particles();
+        stats.particles = self.particles.len();
+        stats
+    }
+
+    /// Runs a single substep of `sub_dt` without spawning or removing
+    /// particles, to look at a step in slow motion.
+    pub fn substep(&mut self, sub_dt: f32) -> StepStats {
+        self.set_sub_dt(sub_dt);
+
+        let mut stats = StepStats {
+            frame: self.frame,
+            substeps: 1,
+            particles: self.particles.len(),
+            ..StepStats::default()
+        };
+        self.run_substep(sub_dt, &mut stats);
+        stats
+    }
+
+    /// Number of substeps the next step of `dt` will be split into.
+    pub fn sub_step_count(&self, dt: f32) -> u32 {
+        let (min, max, max_travel) = match self.config.sub_steps {
+            Substeps::Fixed(count) => return count,
+            Substeps::Adaptive { min, max, max_travel } => (min, max, max_travel),
+        };
+
+        let Some(last_sub_dt) = self.last_sub_dt else {
+            return min;
+        };
+
+        let mut max_speed: f32 = 0.0;
+        let mut min_radius = f32::INFINITY;
+        for i in (0..self.particles.len()).filter(|&i| !self.particles.is_pinned(i)) {
+            max_speed = max_speed.max((self.particles.pos(i) - self.particles.old_pos(i)).length() / last_sub_dt);
+            min_radius = min_radius.min(self.particles.radius(i));
+        }
+        if min_radius == f32::INFINITY {
+            return min;
+        }
+
+        // Include what gravity adds over the step so a falling particle doesn't outrun the estimate
+        let travel = (max_speed + self.config.gravity.length() * dt) * dt;
+        ((travel / (max_travel * min_radius)).ceil() as u32).clamp(min, max)
+    }
+
+    // Velocities are kept as the distance moved in one substep, so they are
+    // rescaled whenever the substep length changes
+    fn set_sub_dt(&mut self, sub_dt: f32) {
+        if let Some(last_sub_dt) = self.last_sub_dt
+            && last_sub_dt != sub_dt
+        {
+            self.particles.scale_velocities(sub_dt / last_sub_dt);
+        }
+        self.last_sub_dt = Some(sub_dt);
+    }
+
+    fn run_substep(&mut self, sub_dt: f32, stats: &mut StepStats) {
+        let mut start = Instant::now();
+        // Apply forces
+        self.particles.accelerate(self.config.gravity);
+
+        stats.forces += start.elapsed();
+        start = Instant::now();
+
+        self.apply_constraints();
+        self.solve_distance_constraints();
+
+        stats.constraints += start.elapsed();
+        start = Instant::now();
+
+        let counts = self.collide();
+        stats.pair_tests += counts.tests;
+        stats.contacts += counts.contacts;
+
+        stats.collisions += start.elapsed();
+        start = Instant::now();
+
+        // Update positions
+        self.particles.update(sub_dt);
+
+        stats.integration += start.elapsed();
+    }
+
+    pub fn apply_constraints(&mut self) {
+        let damping = self.config.damping;
+        let particles = &mut self.particles;
+        let boundary = &self.config.boundary;
+
+        // Most particles are nowhere near the walls, rule them out in bulk
+        boundary.flag_contacts(&particles.x, &particles.y, &particles.radius, &mut self.near_walls);
+
+        for i in 0..particles.len() {
+            if particles.is_pinned(i) {
+                continue;
+            }
+
+            if self.near_walls[i]
+                && let Some((n, penetration)) = boundary.contact(particles.pos(i), particles.radius(i))
+            {
+                // Move the particle back inside the boundary
+                Self::bounce(particles, i, n, penetration, damping);
+            }
+
+            for collider in &self.config.colliders {
+                if let Some((n, penetration)) = collider.contact(particles.pos(i), particles.radius(i)) {
+                    // Move the particle out of the obstacle
+                    Self::bounce(particles, i, n, penetration, damping);
+                }
+            }
+        }
+    }
+
+    fn bounce(particles: &mut Particles, i: usize, n: Vec2, penetration: f32, damping: f32) {
+        let pos = particles.pos(i) + n * penetration;
+
+        // Reflect the velocity (correctly modify old_pos)
+        let vel = pos - particles.old_pos(i);
+        particles.set_pos(i, pos);
+        particles.set_old_pos(i, pos - reflect_vec2(vel, n) * damping);
+    }
+
+    pub fn solve_distance_constraints(&mut self) {
+        for constraint in &self.constraints {
+            constraint.solve(&mut self.particles);
+        }
+    }
+
+    pub fn solve_collisions(&mut self) {
+        self.collide();
+    }
+
+    fn collide(&mut self) -> PairCounts {
+        // Cells must be wide enough for the largest particle
+        let max_radius = self.particles.radius.iter().fold(0.0, |max: f32, &r| max.max(r));
+        if max_radius == 0.0 {
+            return PairCounts::default();
+        }
+        if !self.grid.is_sized_for(max_radius * 2.0) {
+            self.grid = CollisionGrid::new(self.config.width, self.config.height, max_radius * 2.0);
+        }
+        self.grid.rebuild(&self.particles);
+
+        // Gather into cell order so the solver can hand out disjoint slices
+        let order = self.grid.order();
+        self.bodies.clear();
+        let particles = &self.particles;
+        self.bodies.extend(order.iter().map(|&i| Body {
+            pos: particles.pos(i),
+            radius: particles.radius[i],
+            inverse_mass: particles.inverse_mass[i],
+            contacts: 0,
+            pressure: 0.0,
+        }));
+
+        let counts = solver::solve(&self.grid, &mut self.bodies);
+
+        for (body, &i) in self.bodies.iter().zip(order) {
+            self.particles.set_pos(i, body.pos);
+            self.particles.contacts[i] = body.contacts;
+            self.particles.pressure[i] = body.pressure;
+        }
+        counts
+    }
+}
+
+impl Default for VerletSimulation {
+    fn default() -> Self {
+        Self::new(SimulationConfig::default()).expect("default config is valid")
+    }
+}
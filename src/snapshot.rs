@@ -0,0 +1,315 @@
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use crate::config::{ConfigError, SimulationConfig};
+use crate::constraint::DistanceConstraint;
+use crate::emitter::Emitter;
+use crate::particle::Particle;
+use crate::particles::Particles;
+use crate::rng::Rng;
+use crate::simulation::VerletSimulation;
+
+// Binary layout, all little endian:
+//   magic "VRLT", version u32, frame u64, rng state u64,
+//   a u8 flag followed by the last substep length f32 if there was a step,
+//   config and emitters as length-prefixed JSON strings,
+//   the next particle id u64,
+//   particle count u64, then id u64, pos, old_pos, acceleration, radius, inverse_mass as f32s,
+//     age u32, a u8 flag followed by the lifetime u32 if it is set,
+//     and a u8 flag followed by the RGBA colour bytes if it is set,
+//   constraint count u64, then a u64, b u64, rest_length f32, stiffness f32.
+const MAGIC: &[u8; 4] = b"VRLT";
+const VERSION: u32 = 6;
+
+#[derive(Debug)]
+pub enum SnapshotError {
+    Io(std::io::Error),
+    Format(String),
+    Config(ConfigError),
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SnapshotError::Io(err) => write!(f, "could not access snapshot: {err}"),
+            SnapshotError::Format(msg) => write!(f, "malformed snapshot: {msg}"),
+            SnapshotError::Config(err) => write!(f, "snapshot {err}"),
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {}
+
+impl From<std::io::Error> for SnapshotError {
+    fn from(err: std::io::Error) -> Self {
+        SnapshotError::Io(err)
+    }
+}
+
+impl From<ConfigError> for SnapshotError {
+    fn from(err: ConfigError) -> Self {
+        SnapshotError::Config(err)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct Snapshot {
+    version: u32,
+    frame: u64,
+    rng: Rng,
+    last_sub_dt: Option<f32>,
+    config: SimulationConfig,
+    emitters: Vec<Emitter>,
+    next_id: u64,
+    particles: Vec<Particle>,
+    constraints: Vec<DistanceConstraint>,
+}
+
+impl VerletSimulation {
+    /// Writes the full state to `path`. Files ending in `.json` are written as
+    /// readable JSON, anything else uses the compact binary format.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), SnapshotError> {
+        let path = path.as_ref();
+        let bytes = if is_json(path) {
+            let snapshot = Snapshot {
+                version: VERSION,
+                frame: self.frame,
+                rng: self.rng.clone(),
+                last_sub_dt: self.last_sub_dt,
+                config: self.config.clone(),
+                emitters: self.emitters.clone(),
+                next_id: self.particles.next_id,
+                particles: Vec::from(&self.particles),
+                constraints: self.constraints.clone(),
+            };
+            serde_json::to_vec_pretty(&snapshot).map_err(|err| SnapshotError::Format(err.to_string()))?
+        } else {
+            self.to_bytes()
+        };
+
+        fs::write(path, bytes)?;
+        Ok(())
+    }
+
+    /// Restores a simulation written by [`VerletSimulation::save`].
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, SnapshotError> {
+        let path = path.as_ref();
+        let bytes = fs::read(path)?;
+
+        let snapshot = if is_json(path) {
+            serde_json::from_slice(&bytes).map_err(|err| SnapshotError::Format(err.to_string()))?
+        } else {
+            Self::snapshot_from_bytes(&bytes)?
+        };
+
+        if snapshot.version != VERSION {
+            return Err(SnapshotError::Format(format!("unsupported version {}", snapshot.version)));
+        }
+        let len = snapshot.particles.len();
+        if snapshot.constraints.iter().any(|c| c.a >= len || c.b >= len) {
+            return Err(SnapshotError::Format("constraint refers to a missing particle".to_string()));
+        }
+        for emitter in &snapshot.emitters {
+            emitter.validate().map_err(SnapshotError::Format)?;
+        }
+        if let Some(particle) = snapshot.particles.iter().find(|p| {
+            !(p.radius > 0.0 && p.radius.is_finite() && p.inverse_mass >= 0.0 && p.inverse_mass.is_finite())
+        }) {
+            return Err(SnapshotError::Format(format!("invalid particle: {particle:?}")));
+        }
+        let mut ids: Vec<u64> = snapshot.particles.iter().map(|p| p.id).collect();
+        ids.sort_unstable();
+        if ids.windows(2).any(|pair| pair[0] == pair[1]) || ids.last().is_some_and(|&id| id >= snapshot.next_id) {
+            return Err(SnapshotError::Format("particle ids are not unique".to_string()));
+        }
+
+        let mut simulation = VerletSimulation::new(snapshot.config)?;
+        simulation.particles = Particles::new();
+        for particle in snapshot.particles {
+            simulation.particles.push_with_id(particle);
+        }
+        simulation.particles.next_id = snapshot.next_id;
+        simulation.constraints = snapshot.constraints;
+        simulation.emitters = snapshot.emitters;
+        simulation.frame = snapshot.frame;
+        simulation.rng = snapshot.rng;
+        simulation.last_sub_dt = snapshot.last_sub_dt;
+        Ok(simulation)
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let config = serde_json::to_vec(&self.config).expect("config is serializable");
+        let emitters = serde_json::to_vec(&self.emitters).expect("emitters are serializable");
+
+        let mut out = Vec::new();
+        out.extend_from_slice(MAGIC);
+        out.extend_from_slice(&VERSION.to_le_bytes());
+        out.extend_from_slice(&self.frame.to_le_bytes());
+        out.extend_from_slice(&self.rng.state().to_le_bytes());
+        match self.last_sub_dt {
+            Some(sub_dt) => {
+                out.push(1);
+                out.extend_from_slice(&sub_dt.to_le_bytes());
+            }
+            None => out.push(0),
+        }
+        out.extend_from_slice(&(config.len() as u64).to_le_bytes());
+        out.extend_from_slice(&config);
+        out.extend_from_slice(&(emitters.len() as u64).to_le_bytes());
+        out.extend_from_slice(&emitters);
+
+        out.extend_from_slice(&self.particles.next_id.to_le_bytes());
+        out.extend_from_slice(&(self.particles.len() as u64).to_le_bytes());
+        for particle in &self.particles {
+            out.extend_from_slice(&particle.id.to_le_bytes());
+            for value in [particle.pos, particle.old_pos, particle.acceleration] {
+                out.extend_from_slice(&value.x.to_le_bytes());
+                out.extend_from_slice(&value.y.to_le_bytes());
+            }
+            out.extend_from_slice(&particle.radius.to_le_bytes());
+            out.extend_from_slice(&particle.inverse_mass.to_le_bytes());
+            out.extend_from_slice(&particle.age.to_le_bytes());
+            match particle.lifetime {
+                Some(lifetime) => {
+                    out.push(1);
+                    out.extend_from_slice(&lifetime.to_le_bytes());
+                }
+                None => out.push(0),
+            }
+            match particle.color {
+                Some(color) => {
+                    out.push(1);
+                    out.extend_from_slice(&color);
+                }
+                None => out.push(0),
+            }
+        }
+
+        out.extend_from_slice(&(self.constraints.len() as u64).to_le_bytes());
+        for constraint in &self.constraints {
+            out.extend_from_slice(&(constraint.a as u64).to_le_bytes());
+            out.extend_from_slice(&(constraint.b as u64).to_le_bytes());
+            out.extend_from_slice(&constraint.rest_length.to_le_bytes());
+            out.extend_from_slice(&constraint.stiffness.to_le_bytes());
+        }
+
+        out
+    }
+
+    fn snapshot_from_bytes(bytes: &[u8]) -> Result<Snapshot, SnapshotError> {
+        let mut reader = Reader { bytes };
+        if reader.take(4)? != MAGIC {
+            return Err(SnapshotError::Format("not a snapshot file".to_string()));
+        }
+
+        let version = reader.u32()?;
+        if version != VERSION {
+            return Err(SnapshotError::Format(format!("unsupported version {version}")));
+        }
+        let frame = reader.u64()?;
+        let rng = Rng::from_state(reader.u64()?);
+        let last_sub_dt = match reader.take(1)?[0] {
+            0 => None,
+            _ => Some(reader.f32()?),
+        };
+        let config = reader.json()?;
+        let emitters = reader.json()?;
+
+        let next_id = reader.u64()?;
+        let count = reader.count()?;
+        let mut particles = Vec::with_capacity(count.min(bytes.len()));
+        for _ in 0..count {
+            particles.push(Particle {
+                id: reader.u64()?,
+                pos: reader.vec2()?,
+                old_pos: reader.vec2()?,
+                acceleration: reader.vec2()?,
+                radius: reader.f32()?,
+                inverse_mass: reader.f32()?,
+                age: reader.u32()?,
+                lifetime: match reader.take(1)?[0] {
+                    0 => None,
+                    _ => Some(reader.u32()?),
+                },
+                color: match reader.take(1)?[0] {
+                    0 => None,
+                    _ => Some(reader.array()?),
+                },
+            });
+        }
+
+        let count = reader.count()?;
+        let mut constraints = Vec::with_capacity(count.min(bytes.len()));
+        for _ in 0..count {
+            constraints.push(DistanceConstraint {
+                a: reader.count()?,
+                b: reader.count()?,
+                rest_length: reader.f32()?,
+                stiffness: reader.f32()?,
+            });
+        }
+
+        Ok(Snapshot {
+            version,
+            frame,
+            rng,
+            last_sub_dt,
+            config,
+            emitters,
+            next_id,
+            particles,
+            constraints,
+        })
+    }
+}
+
+fn is_json(path: &Path) -> bool {
+    path.extension().and_then(|ext| ext.to_str()) == Some("json")
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], SnapshotError> {
+        if self.bytes.len() < len {
+            return Err(SnapshotError::Format("unexpected end of file".to_string()));
+        }
+        let (head, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        Ok(head)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], SnapshotError> {
+        Ok(self.take(N)?.try_into().expect("took exactly N bytes"))
+    }
+
+    fn u32(&mut self) -> Result<u32, SnapshotError> {
+        Ok(u32::from_le_bytes(self.array()?))
+    }
+
+    fn u64(&mut self) -> Result<u64, SnapshotError> {
+        Ok(u64::from_le_bytes(self.array()?))
+    }
+
+    fn count(&mut self) -> Result<usize, SnapshotError> {
+        usize::try_from(self.u64()?).map_err(|_| SnapshotError::Format("length out of range".to_string()))
+    }
+
+    fn f32(&mut self) -> Result<f32, SnapshotError> {
+        Ok(f32::from_le_bytes(self.array()?))
+    }
+
+    fn json<T: serde::de::DeserializeOwned>(&mut self) -> Result<T, SnapshotError> {
+        let len = self.count()?;
+        serde_json::from_slice(self.take(len)?).map_err(|err| SnapshotError::Format(err.to_string()))
+    }
+
+    fn vec2(&mut self) -> Result<Vec2, SnapshotError> {
+        Ok(Vec2::new(self.f32()?, self.f32()?))
+    }
+}
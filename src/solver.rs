@@ -0,0 +1,205 @@
+use glam::Vec2;
+use std::ops::Range;
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use crate::grid::CollisionGrid;
+
+// Rows of grid cells per band. Bands are fixed by the grid, never by the
+// thread count, so the result is the same however many threads run them.
+const BAND_ROWS: usize = 4;
+
+/// The part of a particle the contact solver needs, gathered in cell order.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Body {
+    pub pos: Vec2,
+    pub radius: f32,
+    pub inverse_mass: f32,
+    // Filled in by the solver
+    pub contacts: u32,
+    pub pressure: f32,
+}
+
+/// How many pairs the solver looked at and how many of them overlapped.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct PairCounts {
+    pub tests: u64,
+    pub contacts: u64,
+}
+
+impl std::ops::Add for PairCounts {
+    type Output = PairCounts;
+
+    fn add(self, other: PairCounts) -> PairCounts {
+        PairCounts {
+            tests: self.tests + other.tests,
+            contacts: self.contacts + other.contacts,
+        }
+    }
+}
+
+// A band of rows together with the bodies in it
+struct Band<'a> {
+    rows: Range<usize>,
+    offset: usize,
+    bodies: &'a mut [Body],
+}
+
+/// Resolves every overlapping pair once. `bodies` must be in the grid's
+/// cell order.
+///
+/// The rows are cut into bands. First the pairs inside each band are solved,
+/// all bands at once, then the pairs across each seam between two bands. A
+/// band never shares a body with another band, and neither does a seam, so
+/// each pass can run in parallel without locks or unsafe code.
+pub(crate) fn solve(grid: &CollisionGrid, bodies: &mut [Body]) -> PairCounts {
+    let bands = (0..grid.rows)
+        .step_by(BAND_ROWS)
+        .map(|start| start..(start + BAND_ROWS).min(grid.rows));
+    let inside = for_each_band(split_bands(grid, bodies, bands), |band| solve_band(grid, band));
+
+    // Seams cover the last row of one band and the first row of the next
+    let seams = (BAND_ROWS..grid.rows).step_by(BAND_ROWS).map(|start| start - 1..start + 1);
+    let across = for_each_band(split_bands(grid, bodies, seams), |band| solve_seam(grid, band));
+    inside + across
+}
+
+// Runs `f` on every band and adds up the counts. Integer sums don't depend
+// on the order, so the totals are deterministic too.
+#[cfg(feature = "parallel")]
+fn for_each_band(bands: Vec<Band>, f: impl Fn(Band) -> PairCounts + Sync + Send) -> PairCounts {
+    bands.into_par_iter().map(f).reduce(PairCounts::default, |a, b| a + b)
+}
+
+#[cfg(not(feature = "parallel"))]
+fn for_each_band(bands: Vec<Band>, f: impl Fn(Band) -> PairCounts) -> PairCounts {
+    bands.into_iter().map(f).fold(PairCounts::default(), |a, b| a + b)
+}
+
+// Hands out the bodies of each band. The row ranges must be ascending and
+// not overlap.
+fn split_bands<'a>(
+    grid: &CollisionGrid,
+    bodies: &'a mut [Body],
+    rows: impl Iterator<Item = Range<usize>>,
+) -> Vec<Band<'a>> {
+    let mut bands = Vec::new();
+    let mut rest = bodies;
+    let mut consumed = 0;
+
+    for rows in rows {
+        let range = grid.rows_range(rows.clone());
+        let (_, tail) = rest.split_at_mut(range.start - consumed);
+        let (band, tail) = tail.split_at_mut(range.len());
+        rest = tail;
+        consumed = range.end;
+
+        bands.push(Band {
+            rows,
+            offset: range.start,
+            bodies: band,
+        });
+    }
+
+    bands
+}
+
+fn solve_band(grid: &CollisionGrid, band: Band) -> PairCounts {
+    let Band { rows, offset, bodies } = band;
+    let mut counts = PairCounts::default();
+
+    for y in rows.clone() {
+        for x in 0..grid.cols {
+            let cell = shift(grid.cell_range(x, y), offset);
+
+            // Pairs inside the cell
+            for i in cell.clone() {
+                for j in i + 1..cell.end {
+                    solve_pair(bodies, i, j, &mut counts);
+                }
+            }
+
+            // Pairs with the neighbouring cells inside the band
+            for (dx, dy) in CollisionGrid::NEIGHBOURS {
+                let nx = x as isize + dx;
+                let ny = y + dy as usize;
+                if nx < 0 || nx >= grid.cols as isize || ny >= rows.end {
+                    continue;
+                }
+
+                let other = shift(grid.cell_range(nx as usize, ny), offset);
+                for i in cell.clone() {
+                    for j in other.clone() {
+                        solve_pair(bodies, i, j, &mut counts);
+                    }
+                }
+            }
+        }
+    }
+
+    counts
+}
+
+fn solve_seam(grid: &CollisionGrid, band: Band) -> PairCounts {
+    let Band { rows, offset, bodies } = band;
+    let y = rows.start;
+    let mut counts = PairCounts::default();
+
+    for x in 0..grid.cols {
+        let cell = shift(grid.cell_range(x, y), offset);
+
+        // Only the neighbours in the row below, the rest belong to a band
+        for (dx, dy) in CollisionGrid::NEIGHBOURS.into_iter().filter(|&(_, dy)| dy == 1) {
+            let nx = x as isize + dx;
+            if nx < 0 || nx >= grid.cols as isize {
+                continue;
+            }
+
+            let other = shift(grid.cell_range(nx as usize, y + dy as usize), offset);
+            for i in cell.clone() {
+                for j in other.clone() {
+                    solve_pair(bodies, i, j, &mut counts);
+                }
+            }
+        }
+    }
+
+    counts
+}
+
+fn shift(range: Range<usize>, offset: usize) -> Range<usize> {
+    range.start - offset..range.end - offset
+}
+
+fn solve_pair(bodies: &mut [Body], i: usize, j: usize, counts: &mut PairCounts) {
+    let (b1, b2) = (bodies[i], bodies[j]);
+    counts.tests += 1;
+
+    let min_dist = b1.radius + b2.radius;
+    let delta = b1.pos - b2.pos;
+    let dist_sq = delta.length_squared();
+
+    if dist_sq < min_dist * min_dist {
+        // Normalize vector only when needed
+        let dist = dist_sq.sqrt();
+        counts.contacts += 1;
+        for k in [i, j] {
+            bodies[k].contacts += 1;
+            bodies[k].pressure += min_dist - dist;
+        }
+
+        let total_inverse_mass = b1.inverse_mass + b2.inverse_mass;
+        if total_inverse_mass == 0.0 {
+            return;
+        }
+
+        // Coincident centers have no direction, so split them along x
+        let n = if dist > 0.0 { delta / dist } else { Vec2::X };
+        let correction = n * ((min_dist - dist) / total_inverse_mass);
+
+        // Move particles, the lighter one takes more of the correction
+        bodies[i].pos += correction * b1.inverse_mass;
+        bodies[j].pos -= correction * b2.inverse_mass;
+    }
+}
@@ -0,0 +1,208 @@
+use serde_json::json;
+use std::collections::VecDeque;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+/// Where the time of one [`VerletSimulation::step`](crate::VerletSimulation::step)
+/// went, summed over its substeps, and how busy the contact solver was.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct StepStats {
+    pub frame: u64,
+    pub substeps: u32,
+    pub particles: usize,
+    // Gravity and other accelerations
+    pub forces: Duration,
+    // Boundary, colliders and distance constraints
+    pub constraints: Duration,
+    pub collisions: Duration,
+    pub integration: Duration,
+    // Pairs from neighbouring cells the solver tested, and how many overlapped
+    pub pair_tests: u64,
+    pub contacts: u64,
+}
+
+impl StepStats {
+    pub fn total(&self) -> Duration {
+        self.forces + self.constraints + self.collisions + self.integration
+    }
+
+    fn phases(&self) -> [(&'static str, Duration); 4] {
+        [
+            ("forces", self.forces),
+            ("constraints", self.constraints),
+            ("collisions", self.collisions),
+            ("integration", self.integration),
+        ]
+    }
+}
+
+impl fmt::Display for StepStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Substeps: {}", self.substeps)?;
+        writeln!(f, "Forces: {:.0}us", micros(self.forces))?;
+        writeln!(f, "Constraints: {:.0}us", micros(self.constraints))?;
+        writeln!(f, "Collisions: {:.0}us", micros(self.collisions))?;
+        writeln!(f, "Integration: {:.0}us", micros(self.integration))?;
+        writeln!(f, "Pair tests: {}", self.pair_tests)?;
+        write!(f, "Contacts: {}", self.contacts)
+    }
+}
+
+/// Average of the last `window` steps, to read timings that would otherwise
+/// flicker from frame to frame.
+#[derive(Debug, Clone)]
+pub struct RollingStats {
+    window: usize,
+    recent: VecDeque<StepStats>,
+}
+
+impl RollingStats {
+    pub fn new(window: usize) -> Self {
+        RollingStats {
+            window: window.max(1),
+            recent: VecDeque::new(),
+        }
+    }
+
+    pub fn push(&mut self, stats: StepStats) {
+        if self.recent.len() == self.window {
+            self.recent.pop_front();
+        }
+        self.recent.push_back(stats);
+    }
+
+    /// The mean of every field over the window. Frame and particle count are
+    /// the latest ones.
+    pub fn average(&self) -> StepStats {
+        let Some(latest) = self.recent.back() else {
+            return StepStats::default();
+        };
+
+        let n = self.recent.len() as u32;
+        let mean_duration = |field: fn(&StepStats) -> Duration| self.recent.iter().map(field).sum::<Duration>() / n;
+        let mean = |field: fn(&StepStats) -> u64| self.recent.iter().map(field).sum::<u64>() / n as u64;
+        StepStats {
+            frame: latest.frame,
+            substeps: mean(|s| s.substeps as u64) as u32,
+            particles: latest.particles,
+            forces: mean_duration(|s| s.forces),
+            constraints: mean_duration(|s| s.constraints),
+            collisions: mean_duration(|s| s.collisions),
+            integration: mean_duration(|s| s.integration),
+            pair_tests: mean(|s| s.pair_tests),
+            contacts: mean(|s| s.contacts),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.recent.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.recent.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.recent.clear();
+    }
+}
+
+/// Every recorded step with the time it finished, for writing out and
+/// comparing between runs.
+#[derive(Debug, Clone)]
+pub struct StatsLog {
+    epoch: Instant,
+    // Microseconds from the epoch to the end of the step
+    steps: Vec<(f64, StepStats)>,
+}
+
+impl Default for StatsLog {
+    fn default() -> Self {
+        StatsLog {
+            epoch: Instant::now(),
+            steps: Vec::new(),
+        }
+    }
+}
+
+impl StatsLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records a step that has just finished.
+    pub fn record(&mut self, stats: StepStats) {
+        self.steps.push((micros(self.epoch.elapsed()), stats));
+    }
+
+    pub fn len(&self) -> usize {
+        self.steps.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    /// One row per step, durations in microseconds.
+    pub fn to_csv(&self) -> String {
+        let mut out = "frame,substeps,particles,forces_us,constraints_us,collisions_us,integration_us,total_us,pair_tests,contacts\n".to_string();
+        for (_, s) in &self.steps {
+            out += &format!(
+                "{},{},{},{:.3},{:.3},{:.3},{:.3},{:.3},{},{}\n",
+                s.frame,
+                s.substeps,
+                s.particles,
+                micros(s.forces),
+                micros(s.constraints),
+                micros(s.collisions),
+                micros(s.integration),
+                micros(s.total()),
+                s.pair_tests,
+                s.contacts
+            );
+        }
+        out
+    }
+
+    /// The steps in the Trace Event Format read by `chrome://tracing` and
+    /// Perfetto. Substeps are summed, so each step shows its phases back to
+    /// back, with the solver counts as counter tracks.
+    pub fn to_chrome_trace(&self) -> String {
+        let mut events = Vec::new();
+        for (end, s) in &self.steps {
+            let mut ts = end - micros(s.total());
+            events.push(json!({
+                "name": "step", "ph": "X", "pid": 1, "tid": 1, "ts": ts, "dur": micros(s.total()),
+                "args": { "frame": s.frame, "substeps": s.substeps, "particles": s.particles },
+            }));
+            for (name, duration) in s.phases() {
+                events.push(json!({ "name": name, "ph": "X", "pid": 1, "tid": 1, "ts": ts, "dur": micros(duration) }));
+                ts += micros(duration);
+            }
+            events.push(json!({
+                "name": "solver", "ph": "C", "pid": 1, "ts": end,
+                "args": { "pair_tests": s.pair_tests, "contacts": s.contacts },
+            }));
+        }
+
+        json!({ "traceEvents": events, "displayTimeUnit": "ms" }).to_string()
+    }
+
+    /// Writes a Chrome trace if `path` ends in `.json`, CSV otherwise.
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let path = path.as_ref();
+        let contents = if path.extension().and_then(|ext| ext.to_str()) == Some("json") {
+            self.to_chrome_trace()
+        } else {
+            self.to_csv()
+        };
+        fs::write(path, contents)
+    }
+}
+
+fn micros(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1e6
+}
@@ -0,0 +1,55 @@
+/// Fixed-step accumulator that decouples simulated time from the render
+/// rate. Real elapsed time is banked and paid out in whole steps of `dt`.
+#[derive(Debug, Clone)]
+pub struct FixedTimestep {
+    pub dt: f32,
+    // Most steps run for a single advance at normal speed, scaled up with the
+    // time scale. Anything beyond that is dropped so a slow frame can't
+    // snowball into ever slower frames.
+    pub max_steps: u32,
+    accumulator: f32,
+}
+
+impl FixedTimestep {
+    pub fn new(dt: f32, max_steps: u32) -> Self {
+        FixedTimestep {
+            dt,
+            max_steps,
+            accumulator: 0.0,
+        }
+    }
+
+    /// Banks `elapsed` seconds and returns how many steps to run now.
+    pub fn advance(&mut self, elapsed: f32) -> u32 {
+        self.advance_scaled(elapsed, 1.0)
+    }
+
+    /// Banks `elapsed` real seconds played back `time_scale` times as fast.
+    /// The step limit grows with the scale, so fast forward isn't clamped
+    /// back down to `max_steps` per advance.
+    pub fn advance_scaled(&mut self, elapsed: f32, time_scale: f32) -> u32 {
+        let time_scale = time_scale.max(0.0);
+        self.accumulator += elapsed.max(0.0) * time_scale;
+
+        let max_steps = self.max_steps * (time_scale.ceil() as u32).max(1);
+        let steps = (self.accumulator / self.dt) as u32;
+        if steps > max_steps {
+            // Spiral of death, give up on catching up
+            self.accumulator = 0.0;
+            return max_steps;
+        }
+
+        self.accumulator -= steps as f32 * self.dt;
+        steps
+    }
+
+    /// Fraction of a step banked but not yet simulated, in `[0, 1)`. Used to
+    /// interpolate rendering between steps.
+    pub fn alpha(&self) -> f32 {
+        (self.accumulator / self.dt).clamp(0.0, 1.0)
+    }
+
+    pub fn reset(&mut self) {
+        self.accumulator = 0.0;
+    }
+}
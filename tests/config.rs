@@ -0,0 +1,68 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use verlet::{Boundary, ConfigError, SimulationConfig, Substeps, Vec2};
+
+fn temp_file(name: &str, contents: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("verlet-{}-{name}", std::process::id()));
+    fs::write(&path, contents).unwrap();
+    path
+}
+
+fn load(name: &str, contents: &str) -> Result<SimulationConfig, ConfigError> {
+    let path = temp_file(name, contents);
+    let config = SimulationConfig::load(&path);
+    fs::remove_file(&path).unwrap();
+    config
+}
+
+#[test]
+fn example_config_matches_the_defaults() {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("config.example.toml");
+    let example = SimulationConfig::load(path).unwrap();
+    let defaults = SimulationConfig::default();
+
+    assert_eq!(example.gravity, defaults.gravity);
+    assert_eq!(example.particle_radius, defaults.particle_radius);
+    assert_eq!(example.boundary, defaults.boundary);
+    assert_eq!(example.sub_steps, defaults.sub_steps);
+    assert_eq!(example.emitters.len(), 1);
+}
+
+#[test]
+fn toml_and_json_override_the_defaults() {
+    let toml = load(
+        "override.toml",
+        "gravity = [0.0, 0.0]\nparticle_radius = 3.0\nsub_steps = { min = 1, max = 8, max_travel = 0.25 }\n",
+    )
+    .unwrap();
+    assert_eq!(toml.gravity, Vec2::ZERO);
+    assert_eq!(toml.particle_radius, 3.0);
+    assert_eq!(toml.sub_steps, Substeps::Adaptive { min: 1, max: 8, max_travel: 0.25 });
+    // Everything else keeps its default
+    assert_eq!(toml.width, SimulationConfig::default().width);
+
+    let json = load(
+        "override.json",
+        r#"{ "boundary": { "type": "rect", "min": [0.0, 0.0], "max": [100.0, 50.0] }, "emitters": [] }"#,
+    )
+    .unwrap();
+    assert_eq!(json.boundary, Boundary::Rect { min: Vec2::ZERO, max: Vec2::new(100.0, 50.0) });
+    assert!(json.emitters.is_empty());
+}
+
+#[test]
+fn rejects_typos_and_invalid_values() {
+    let typo = load("typo.toml", "gravty = [0.0, 0.0]\n");
+    assert!(matches!(typo, Err(ConfigError::Parse(_))), "{typo:?}");
+    let nested = load("nested.toml", "[[emitters]]\nintervall = 3\n");
+    assert!(matches!(nested, Err(ConfigError::Parse(_))), "{nested:?}");
+
+    for invalid in ["particle_radius = -1.0", "particle_radius = inf", "damping = 1.5", "sub_steps = 0"] {
+        let config = load("invalid.toml", invalid);
+        assert!(matches!(config, Err(ConfigError::Invalid(_))), "{invalid}: {config:?}");
+    }
+
+    assert!(matches!(load("unknown.yaml", ""), Err(ConfigError::Parse(_))));
+    assert!(matches!(SimulationConfig::load("/nonexistent/config.toml"), Err(ConfigError::Io(_))));
+}
@@ -0,0 +1,152 @@
+use verlet::{Boundary, Collider, SimulationConfig, Vec2};
+
+fn assert_contact(contact: Option<(Vec2, f32)>, normal: Vec2, depth: f32) {
+    let (n, d) = contact.expect("expected a contact");
+    assert!(n.distance(normal) < 1e-4, "normal {n} != {normal}");
+    assert!((d - depth).abs() < 1e-3, "depth {d} != {depth}");
+}
+
+fn square(clockwise: bool) -> Vec<Vec2> {
+    let mut points = vec![
+        Vec2::new(0.0, 0.0),
+        Vec2::new(100.0, 0.0),
+        Vec2::new(100.0, 100.0),
+        Vec2::new(0.0, 100.0),
+    ];
+    if clockwise {
+        points.reverse();
+    }
+    points
+}
+
+#[test]
+fn default_circle_matches_the_original_wall() {
+    // The original wall sat at 250 - PARTICLE_RADIUS and stopped centers 7 units before it
+    let boundary = SimulationConfig::default().boundary;
+    let center = Vec2::new(300.0, 300.0);
+
+    assert_eq!(boundary.contact(center + Vec2::new(236.0, 0.0), 7.0), None);
+    assert_contact(boundary.contact(center + Vec2::new(240.0, 0.0), 7.0), -Vec2::X, 4.0);
+    assert_contact(boundary.contact(center + Vec2::new(0.0, 250.0), 7.0), -Vec2::Y, 14.0);
+}
+
+#[test]
+fn none_boundary_never_touches() {
+    assert_eq!(Boundary::None.contact(Vec2::new(1e6, -1e6), 7.0), None);
+}
+
+#[test]
+fn rect_pushes_back_inside() {
+    let boundary = Boundary::Rect { min: Vec2::ZERO, max: Vec2::new(100.0, 50.0) };
+
+    assert_eq!(boundary.contact(Vec2::new(50.0, 25.0), 5.0), None);
+    assert_contact(boundary.contact(Vec2::new(2.0, 25.0), 5.0), Vec2::X, 3.0);
+    assert_contact(boundary.contact(Vec2::new(50.0, 48.0), 5.0), -Vec2::Y, 3.0);
+    // Corners push out along both axes
+    assert_contact(boundary.contact(Vec2::new(98.0, 2.0), 5.0), Vec2::new(-1.0, 1.0).normalize(), 18f32.sqrt());
+}
+
+#[test]
+fn polygon_boundary_works_in_both_windings() {
+    for clockwise in [false, true] {
+        let boundary = Boundary::Polygon { points: square(clockwise) };
+
+        assert_eq!(boundary.contact(Vec2::new(50.0, 50.0), 5.0), None);
+        assert_contact(boundary.contact(Vec2::new(50.0, 2.0), 5.0), Vec2::Y, 3.0);
+        // Outside entirely, pulled back in past the edge
+        assert_contact(boundary.contact(Vec2::new(110.0, 50.0), 5.0), -Vec2::X, 15.0);
+        assert_contact(boundary.contact(Vec2::new(2.0, 2.0), 5.0), Vec2::ONE.normalize(), 18f32.sqrt());
+    }
+}
+
+#[test]
+fn polygon_boundary_must_be_convex() {
+    let valid = |points: &[[f32; 2]]| {
+        let points = points.iter().map(|&p| Vec2::from(p)).collect();
+        Boundary::Polygon { points }.validate().is_ok()
+    };
+
+    assert!(valid(&[[0.0, 0.0], [100.0, 0.0], [100.0, 100.0], [0.0, 100.0]]));
+    // A collinear point on an edge is still convex
+    assert!(valid(&[[0.0, 0.0], [50.0, 0.0], [100.0, 0.0], [100.0, 100.0], [0.0, 100.0]]));
+    // Notched
+    assert!(!valid(&[[0.0, 0.0], [100.0, 0.0], [50.0, 50.0], [100.0, 100.0], [0.0, 100.0]]));
+    // Repeated point
+    assert!(!valid(&[[0.0, 0.0], [100.0, 0.0], [100.0, 0.0], [100.0, 100.0], [0.0, 100.0]]));
+    // Pentagram, every corner turns the same way but it goes around twice
+    let star: Vec<[f32; 2]> = (0..5)
+        .map(|i| {
+            let angle = i as f32 * 2.0 * std::f32::consts::TAU / 5.0;
+            [angle.cos() * 100.0, angle.sin() * 100.0]
+        })
+        .collect();
+    assert!(!valid(&star));
+    assert!(!valid(&[[0.0, 0.0], [100.0, 0.0]]));
+}
+
+#[test]
+fn annulus_pushes_off_both_rims() {
+    let center = Vec2::new(100.0, 100.0);
+    let boundary = Boundary::Annulus { center, inner_radius: 20.0, outer_radius: 80.0 };
+
+    assert_eq!(boundary.contact(center + Vec2::new(50.0, 0.0), 5.0), None);
+    assert_contact(boundary.contact(center + Vec2::new(0.0, 78.0), 5.0), -Vec2::Y, 3.0);
+    assert_contact(boundary.contact(center + Vec2::new(0.0, -22.0), 5.0), -Vec2::Y, 3.0);
+    // Exactly in the middle there is no direction, it falls back to +x
+    assert_contact(boundary.contact(center, 5.0), Vec2::X, 25.0);
+}
+
+#[test]
+fn segment_and_capsule_push_off_the_closest_point() {
+    let (a, b) = (Vec2::new(0.0, 0.0), Vec2::new(100.0, 0.0));
+
+    let segment = Collider::Segment { a, b };
+    assert_eq!(segment.contact(Vec2::new(50.0, 6.0), 5.0), None);
+    assert_contact(segment.contact(Vec2::new(50.0, 3.0), 5.0), Vec2::Y, 2.0);
+    assert_contact(segment.contact(Vec2::new(50.0, -3.0), 5.0), -Vec2::Y, 2.0);
+    // Past the end the segment is round
+    assert_contact(segment.contact(Vec2::new(103.0, 0.0), 5.0), Vec2::X, 2.0);
+
+    let capsule = Collider::Capsule { a, b, radius: 4.0 };
+    assert_contact(capsule.contact(Vec2::new(50.0, 6.0), 5.0), Vec2::Y, 3.0);
+    assert_contact(capsule.contact(Vec2::new(-6.0, 0.0), 5.0), -Vec2::X, 3.0);
+    // Sitting on the axis, pushed off perpendicular to it
+    assert_contact(capsule.contact(Vec2::new(50.0, 0.0), 5.0), Vec2::Y, 9.0);
+}
+
+#[test]
+fn circle_collider_pushes_outward() {
+    let collider = Collider::Circle { center: Vec2::new(50.0, 50.0), radius: 10.0 };
+
+    assert_eq!(collider.contact(Vec2::new(66.0, 50.0), 5.0), None);
+    assert_contact(collider.contact(Vec2::new(62.0, 50.0), 5.0), Vec2::X, 3.0);
+    assert_contact(collider.contact(Vec2::new(50.0, 50.0), 5.0), Vec2::Y, 15.0);
+}
+
+#[test]
+fn polygon_collider_must_be_convex() {
+    let convex = Collider::Polygon { points: square(false) };
+    let mut notched = square(true);
+    notched.insert(1, Vec2::new(50.0, 50.0));
+    let mut repeated = square(false);
+    repeated.push(Vec2::new(0.0, 100.0));
+
+    assert!(convex.validate().is_ok());
+    assert!(Collider::Polygon { points: notched }.validate().is_err());
+    assert!(Collider::Polygon { points: repeated }.validate().is_err());
+}
+
+#[test]
+fn polygon_collider_works_inside_and_outside() {
+    for clockwise in [false, true] {
+        let collider = Collider::Polygon { points: square(clockwise) };
+
+        assert_eq!(collider.contact(Vec2::new(50.0, 110.0), 5.0), None);
+        // Center outside, against an edge and against a corner
+        assert_contact(collider.contact(Vec2::new(50.0, 103.0), 5.0), Vec2::Y, 2.0);
+        assert_contact(collider.contact(Vec2::new(102.0, 102.0), 5.0), Vec2::ONE.normalize(), 5.0 - 8f32.sqrt());
+        // Center inside, out through the closest edge
+        assert_contact(collider.contact(Vec2::new(50.0, 2.0), 5.0), -Vec2::Y, 7.0);
+        assert_contact(collider.contact(Vec2::new(97.0, 50.0), 5.0), Vec2::X, 8.0);
+    }
+}
@@ -0,0 +1,62 @@
+use verlet::{Collider, Particle, SimulationConfig, Vec2, VerletSimulation};
+
+const STEPS: usize = 300;
+
+fn hash_particles(simulation: &VerletSimulation) -> u64 {
+    // FNV-1a over the raw bits, so any difference in the last ulp shows up
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for particle in &simulation.particles {
+        for value in [particle.pos, particle.old_pos] {
+            for bits in [value.x.to_bits(), value.y.to_bits()] {
+                for byte in bits.to_le_bytes() {
+                    hash ^= byte as u64;
+                    hash = hash.wrapping_mul(0x0100_0000_01b3);
+                }
+            }
+        }
+    }
+    hash
+}
+
+fn run(seed: u64) -> u64 {
+    let config = SimulationConfig {
+        seed,
+        colliders: vec![Collider::Circle { center: Vec2::new(300.0, 350.0), radius: 20.0 }],
+        ..SimulationConfig::default()
+    };
+    let mut simulation = VerletSimulation::new(config).unwrap();
+    simulation.add_rope(Vec2::new(200.0, 200.0), Vec2::new(280.0, 200.0), 6, 1.0);
+
+    for _ in 0..STEPS {
+        // Randomized spawning drawn from the simulation's own stream
+        let x = simulation.rng().range(200.0, 400.0);
+        let radius = simulation.rng().range(3.0, 8.0);
+        let pos = Vec2::new(x, 150.0);
+        simulation.add_particle(Particle::new(pos, pos, radius, radius * radius));
+
+        simulation.step(1.0 / 60.0);
+    }
+
+    hash_particles(&simulation)
+}
+
+#[test]
+fn same_seed_is_bit_identical() {
+    assert_eq!(run(42), run(42));
+}
+
+#[test]
+fn different_seeds_diverge() {
+    assert_ne!(run(42), run(43));
+}
+
+#[cfg(feature = "parallel")]
+#[test]
+fn thread_count_does_not_change_results() {
+    let run_on = |threads| {
+        let pool = rayon::ThreadPoolBuilder::new().num_threads(threads).build().unwrap();
+        pool.install(|| run(42))
+    };
+
+    assert_eq!(run_on(1), run_on(4));
+}
@@ -0,0 +1,261 @@
+use verlet::{
+    DistanceConstraint, Emitter, History, KillZone, Particle, Particles, SimulationConfig, SprayPattern, Substeps,
+    Vec2, VerletSimulation,
+};
+
+fn simulation() -> VerletSimulation {
+    let config = SimulationConfig {
+        emitters: Vec::new(),
+        ..SimulationConfig::default()
+    };
+    VerletSimulation::new(config).unwrap()
+}
+
+fn adaptive() -> VerletSimulation {
+    let config = SimulationConfig {
+        gravity: Vec2::ZERO,
+        sub_steps: Substeps::Adaptive { min: 2, max: 32, max_travel: 0.5 },
+        emitters: Vec::new(),
+        ..SimulationConfig::default()
+    };
+    VerletSimulation::new(config).unwrap()
+}
+
+#[test]
+fn degenerate_ropes_and_rings_add_nothing() {
+    let mut simulation = simulation();
+    let start = Vec2::new(250.0, 300.0);
+
+    assert!(simulation.add_rope(start, Vec2::new(350.0, 300.0), 0, 1.0).is_empty());
+    for segments in 0..3 {
+        assert!(simulation.add_ring(start, 30.0, segments, 1.0).is_empty());
+    }
+    assert!(simulation.particles.is_empty());
+    assert!(simulation.constraints.is_empty());
+
+    let ring = simulation.add_ring(start, 30.0, 3, 1.0);
+    assert_eq!(ring.len(), 3);
+    assert!(simulation.constraints.iter().all(|c| c.a != c.b));
+}
+
+#[test]
+fn distance_constraint_restores_the_rest_length_by_mass() {
+    let at = |x: f32, mass: f32| Particle::new(Vec2::new(x, 0.0), Vec2::new(x, 0.0), 1.0, mass);
+    let mut particles: Particles = [at(0.0, 1.0), at(16.0, 3.0), Particle::pinned(Vec2::new(30.0, 0.0), 1.0)]
+        .into_iter()
+        .collect();
+
+    // Stretched by 6, the light end takes three quarters of it
+    DistanceConstraint::new(0, 1, 10.0, 1.0).solve(&mut particles);
+    assert_eq!(particles.pos(0).x, 4.5);
+    assert_eq!(particles.pos(1).x, 14.5);
+
+    // Against a pinned end the free one takes all of it
+    DistanceConstraint::new(1, 2, 10.0, 1.0).solve(&mut particles);
+    assert_eq!(particles.pos(1).x, 20.0);
+    assert_eq!(particles.pos(2).x, 30.0);
+
+    // Half stiffness closes half the gap
+    DistanceConstraint::new(1, 2, 20.0, 0.5).solve(&mut particles);
+    assert_eq!(particles.pos(1).x, 15.0);
+}
+
+#[test]
+fn moved_pinned_particle_comes_to_rest() {
+    let mut simulation = simulation();
+    let start = Vec2::new(300.0, 300.0);
+    let i = simulation.add_particle(Particle::pinned(start, 5.0));
+    simulation.step(1.0 / 60.0);
+
+    let end = start + Vec2::new(10.0, 0.0);
+    simulation.particles.move_to(i, end);
+    assert!(simulation.velocity(i).x > 0.0);
+
+    simulation.step(1.0 / 60.0);
+    assert_eq!(simulation.particles.pos(i), end);
+    assert_eq!(simulation.velocity(i), Vec2::ZERO);
+}
+
+#[test]
+fn rejects_invalid_emitters() {
+    let mut simulation = simulation();
+    let sweep = Emitter {
+        pattern: SprayPattern::Sweep { period: 0 },
+        ..Emitter::default()
+    };
+    let silent = Emitter { interval: 0, ..Emitter::default() };
+
+    assert!(simulation.add_emitter(sweep).is_err());
+    assert!(simulation.add_emitter(silent).is_err());
+    assert_eq!(simulation.add_emitter(Emitter::default()), Ok(0));
+    simulation.step(1.0 / 60.0);
+    assert_eq!(simulation.particles.len(), 1);
+}
+
+#[test]
+fn killing_the_middle_of_a_rope_keeps_the_other_links() {
+    let mut simulation = simulation();
+    simulation.config.gravity = Vec2::ZERO;
+    let rope = simulation.add_rope(Vec2::new(260.0, 300.0), Vec2::new(340.0, 300.0), 4, 1.0);
+    let before: Vec<Vec2> = rope.map(|i| simulation.particles.pos(i)).collect();
+    simulation.config.kill_zones.push(KillZone::Circle { center: before[2], radius: 3.0 });
+    simulation.step(1.0 / 60.0);
+
+    // The last particle moved into the dead one's slot, its link must follow it
+    let particles = &simulation.particles;
+    assert_eq!(particles.len(), 4);
+    assert_eq!(particles.pos(2), before[4]);
+    let mut links: Vec<_> = simulation
+        .constraints
+        .iter()
+        .map(|c| (particles.pos(c.a).x, particles.pos(c.b).x))
+        .collect();
+    links.sort_by(|a, b| a.0.total_cmp(&b.0));
+    assert_eq!(links, [(before[0].x, before[1].x), (before[3].x, before[4].x)]);
+}
+
+#[test]
+fn particles_expire_after_their_lifetime() {
+    let mut simulation = simulation();
+    let mut particle = Particle::new(Vec2::new(300.0, 300.0), Vec2::new(300.0, 300.0), 5.0, 1.0);
+    particle.lifetime = Some(3);
+    simulation.add_particle(particle);
+    simulation.add_particle(Particle::new(Vec2::new(200.0, 300.0), Vec2::new(200.0, 300.0), 5.0, 1.0));
+
+    for _ in 0..2 {
+        simulation.step(1.0 / 60.0);
+    }
+    assert_eq!(simulation.particles.len(), 2);
+
+    simulation.step(1.0 / 60.0);
+    assert_eq!(simulation.particles.len(), 1);
+    assert_eq!(simulation.particles.get(0).unwrap().lifetime, None);
+}
+
+#[test]
+fn rewinding_restores_the_recorded_step() {
+    let mut simulation = VerletSimulation::default();
+    let mut history = History::new(2);
+    let mut recorded = Vec::new();
+    for _ in 0..3 {
+        history.record(&simulation);
+        recorded.push((simulation.frame(), Vec::from(&simulation.particles)));
+        simulation.step(1.0 / 60.0);
+    }
+
+    // Only the last two fit
+    for (frame, particles) in recorded.iter().rev().take(2) {
+        assert!(history.rewind(&mut simulation));
+        assert_eq!(simulation.frame(), *frame);
+        assert_eq!(format!("{:?}", Vec::from(&simulation.particles)), format!("{particles:?}"));
+    }
+    assert!(!history.rewind(&mut simulation));
+}
+
+#[test]
+fn previous_positions_span_the_whole_step() {
+    let mut simulation = simulation();
+    let start = Vec2::new(300.0, 300.0);
+    let i = simulation.add_particle(Particle::new(start, start, 5.0, 1.0));
+    for _ in 0..3 {
+        let before = simulation.particles.pos(i);
+        simulation.step(1.0 / 60.0);
+
+        // Not just the last of the substeps
+        assert_eq!(simulation.particles.previous_pos(i), before);
+        assert_ne!(simulation.particles.old_pos(i), before);
+    }
+}
+
+#[test]
+fn ids_follow_particles_through_removal() {
+    let mut simulation = simulation();
+    for x in [200.0, 300.0, 400.0] {
+        simulation.add_particle(Particle::new(Vec2::new(x, 300.0), Vec2::new(x, 300.0), 5.0, 1.0));
+    }
+    assert_eq!(simulation.particles.ids(), [0, 1, 2]);
+
+    simulation.remove_particle(0);
+    assert_eq!(simulation.particles.ids(), [2, 1]);
+    assert_eq!(simulation.particles.pos(0).x, 400.0);
+    assert_eq!(simulation.particles.index_of(2), Some(0));
+    assert_eq!(simulation.particles.index_of(0), None);
+
+    // Never reused, even after clearing
+    simulation.add_rope(Vec2::new(200.0, 250.0), Vec2::new(400.0, 250.0), 2, 1.0);
+    simulation.clear_particles();
+    assert!(simulation.constraints.is_empty());
+    let i = simulation.add_particle(Particle::new(Vec2::new(300.0, 300.0), Vec2::new(300.0, 300.0), 5.0, 1.0));
+    assert_eq!(simulation.particles.id(i), 6);
+}
+
+#[test]
+fn tiny_particles_get_a_bounded_grid() {
+    // One diameter wide cells would be about 3.6e9 of them
+    let config = SimulationConfig {
+        particle_radius: 0.005,
+        emitters: Vec::new(),
+        ..SimulationConfig::default()
+    };
+    let mut simulation = VerletSimulation::new(config).unwrap();
+    for x in [300.0, 300.006] {
+        simulation.add_particle(Particle::new(Vec2::new(x, 300.0), Vec2::new(x, 300.0), 0.005, 1.0));
+    }
+    simulation.solve_collisions();
+
+    let distance = simulation.particles.pos(0).distance(simulation.particles.pos(1));
+    assert!((distance - 0.01).abs() < 1e-4, "{distance}");
+}
+
+#[test]
+fn adaptive_substeps_follow_the_fastest_particle() {
+    let mut simulation = adaptive();
+    let start = Vec2::new(300.0, 300.0);
+    let fast = simulation.add_particle(Particle::new(start, start, 5.0, 1.0));
+    simulation.step(1.0 / 60.0);
+    assert_eq!(simulation.sub_step_count(1.0 / 60.0), 2);
+
+    // 6000 units per second is 100 per step, far more than 32 substeps of 2.5
+    simulation.particles.set_old_pos(fast, start - Vec2::new(0.0, 50.0));
+    assert_eq!(simulation.sub_step_count(1.0 / 60.0), 32);
+
+    // Pinned particles don't count
+    let mut pinned = simulation.particles.get(fast).unwrap();
+    pinned.pin();
+    simulation.particles.set(fast, pinned);
+    assert_eq!(simulation.sub_step_count(1.0 / 60.0), 2);
+}
+
+#[test]
+fn changing_the_substep_count_keeps_velocities() {
+    let mut simulation = adaptive();
+    let start = Vec2::new(300.0, 300.0);
+    let i = simulation.add_particle(Particle::new(start, start, 5.0, 1.0));
+    simulation.step(1.0 / 60.0);
+
+    simulation.particles.set_old_pos(i, start - Vec2::new(10.0, 0.0));
+    let before = simulation.velocity(i);
+    let count = simulation.sub_step_count(1.0 / 60.0);
+    assert_ne!(count, 2);
+
+    let stats = simulation.step(1.0 / 60.0);
+    assert_eq!(stats.substeps, count);
+    let after = simulation.velocity(i);
+    assert!(after.distance(before) < 1e-2, "{before} != {after}");
+    assert!((simulation.particles.pos(i).x - (start.x + before.x / 60.0)).abs() < 1e-2);
+}
+
+#[test]
+fn fast_particles_stay_inside_the_boundary() {
+    let mut simulation = adaptive();
+    let start = Vec2::new(300.0, 300.0);
+    simulation.add_particle(Particle::new(start, start - Vec2::new(40.0, 0.0), 7.0, 1.0));
+
+    // The wall stops centers at 236, the last substep may carry them at most
+    // max_travel radii past that before the next step pushes them back
+    for _ in 0..120 {
+        simulation.step(1.0 / 60.0);
+        let distance = simulation.particles.pos(0).distance(start);
+        assert!(distance <= 236.0 + 0.5 * 7.0 + 1e-3, "{distance}");
+    }
+}
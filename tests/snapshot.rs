@@ -0,0 +1,99 @@
+use std::fs;
+use std::path::PathBuf;
+
+use verlet::{Particle, SimulationConfig, SnapshotError, SprayPattern, Vec2, VerletSimulation};
+
+const DT: f32 = 1.0 / 60.0;
+
+fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("verlet-{}-{name}", std::process::id()))
+}
+
+// A random fountain plus a rope and a coloured particle that dies after the
+// save, so every saved field is in use
+fn scene() -> VerletSimulation {
+    let mut config = SimulationConfig { seed: 7, ..SimulationConfig::default() };
+    config.emitters[0].pattern = SprayPattern::Random;
+    let mut simulation = VerletSimulation::new(config).unwrap();
+    simulation.add_rope(Vec2::new(200.0, 250.0), Vec2::new(280.0, 250.0), 5, 0.8);
+
+    let mut particle = Particle::new(Vec2::new(350.0, 300.0), Vec2::new(349.0, 300.0), 5.0, 2.0);
+    particle.lifetime = Some(60);
+    particle.color = Some([10, 20, 30, 255]);
+    simulation.add_particle(particle);
+
+    for _ in 0..30 {
+        simulation.step(DT);
+    }
+    simulation
+}
+
+// Debug prints floats with every digit needed to round trip, so equal text means equal bits
+fn state(simulation: &VerletSimulation) -> String {
+    format!(
+        "{} {:?} {:?} {:?}",
+        simulation.frame(),
+        Vec::from(&simulation.particles),
+        simulation.constraints,
+        simulation.emitters
+    )
+}
+
+fn assert_round_trip(name: &str) {
+    let path = temp_path(name);
+    let mut original = scene();
+    original.save(&path).unwrap();
+    let mut loaded = VerletSimulation::load(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(state(&loaded), state(&original));
+    for _ in 0..60 {
+        original.step(DT);
+        loaded.step(DT);
+    }
+    assert_eq!(state(&loaded), state(&original));
+}
+
+#[test]
+fn binary_round_trip_continues_identically() {
+    assert_round_trip("round-trip.bin");
+}
+
+#[test]
+fn json_round_trip_continues_identically() {
+    assert_round_trip("round-trip.json");
+}
+
+#[test]
+fn rejects_truncated_and_foreign_files() {
+    let path = temp_path("broken.bin");
+    scene().save(&path).unwrap();
+    let bytes = fs::read(&path).unwrap();
+
+    fs::write(&path, &bytes[..bytes.len() - 3]).unwrap();
+    assert!(matches!(VerletSimulation::load(&path), Err(SnapshotError::Format(_))));
+
+    let mut foreign = bytes.clone();
+    foreign[..4].copy_from_slice(b"PNG\0");
+    fs::write(&path, &foreign).unwrap();
+    assert!(matches!(VerletSimulation::load(&path), Err(SnapshotError::Format(_))));
+
+    fs::remove_file(&path).unwrap();
+    assert!(matches!(VerletSimulation::load(&path), Err(SnapshotError::Io(_))));
+
+    // Edited by hand into values the simulation can't run. The live emitters
+    // come after the config's copy, so edit the last match.
+    let path = temp_path("broken.json");
+    scene().save(&path).unwrap();
+    let json = fs::read_to_string(&path).unwrap();
+    let edits = [
+        (r#""type": "random""#, r#""type": "sweep", "period": 0"#),
+        (r#""radius": 5.0"#, r#""radius": -5.0"#),
+    ];
+    for (from, to) in edits {
+        let at = json.rfind(from).unwrap();
+        fs::write(&path, format!("{}{to}{}", &json[..at], &json[at + from.len()..])).unwrap();
+        assert!(matches!(VerletSimulation::load(&path), Err(SnapshotError::Format(_))), "{to}");
+    }
+    fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,191 @@
+// Small scenes on purpose, so the suite also runs under Miri:
+//   cargo +nightly miri test --no-default-features --test solver
+// With rayon, crossbeam trips Stacked Borrows and leaves its pool running, so use
+//   MIRIFLAGS="-Zmiri-tree-borrows -Zmiri-ignore-leaks" cargo +nightly miri test --test solver
+
+use verlet::{Particle, SimulationConfig, Vec2, VerletSimulation};
+
+const RADIUS: f32 = 5.0;
+
+fn simulation(particles: &[Particle]) -> VerletSimulation {
+    let config = SimulationConfig {
+        width: 200.0,
+        height: 200.0,
+        emitters: Vec::new(),
+        ..SimulationConfig::default()
+    };
+    let mut simulation = VerletSimulation::new(config).unwrap();
+    for particle in particles {
+        simulation.add_particle(particle.clone());
+    }
+    simulation
+}
+
+fn particle(x: f32, y: f32) -> Particle {
+    Particle::new(Vec2::new(x, y), Vec2::new(x, y), RADIUS, 1.0)
+}
+
+fn assert_close(a: f32, b: f32) {
+    assert!((a - b).abs() < 1e-3, "{a} != {b}");
+}
+
+#[test]
+fn separates_an_overlapping_pair() {
+    let mut simulation = simulation(&[particle(50.0, 50.0), particle(56.0, 50.0)]);
+    simulation.solve_collisions();
+
+    let (a, b) = (simulation.particles.pos(0), simulation.particles.pos(1));
+    assert_close(a.distance(b), RADIUS * 2.0);
+    assert_close((a.x + b.x) / 2.0, 53.0);
+    assert_close(a.y, 50.0);
+}
+
+#[test]
+fn leaves_touching_particles_alone() {
+    let mut simulation = simulation(&[particle(50.0, 50.0), particle(60.0, 50.0)]);
+    simulation.solve_collisions();
+
+    assert_eq!(simulation.particles.pos(0), Vec2::new(50.0, 50.0));
+    assert_eq!(simulation.particles.pos(1), Vec2::new(60.0, 50.0));
+}
+
+#[test]
+fn separates_coincident_particles() {
+    let mut pair = simulation(&[particle(50.0, 50.0), particle(50.0, 50.0)]);
+    pair.solve_collisions();
+
+    let (a, b) = (pair.particles.pos(0), pair.particles.pos(1));
+    assert_close(a.distance(b), RADIUS * 2.0);
+    assert_close(a.y, 50.0);
+
+    // Like a burst from one emitter, all spawned on the same point
+    let mut burst = simulation(&vec![particle(150.0, 150.0); 5]);
+    burst.step(1.0 / 60.0);
+    assert!(burst.particles.iter().all(|p| p.pos.is_finite() && p.old_pos.is_finite()));
+}
+
+#[test]
+fn heavier_particle_moves_less() {
+    let heavy = Particle::new(Vec2::new(56.0, 50.0), Vec2::new(56.0, 50.0), RADIUS, 3.0);
+    let mut simulation = simulation(&[particle(50.0, 50.0), heavy]);
+    simulation.solve_collisions();
+
+    assert_close(50.0 - simulation.particles.pos(0).x, 3.0);
+    assert_close(simulation.particles.pos(1).x - 56.0, 1.0);
+}
+
+#[test]
+fn pinned_particle_is_not_pushed() {
+    let pinned = Particle::pinned(Vec2::new(56.0, 50.0), RADIUS);
+    let mut simulation = simulation(&[particle(50.0, 50.0), pinned]);
+    simulation.solve_collisions();
+
+    assert_eq!(simulation.particles.pos(1), Vec2::new(56.0, 50.0));
+    assert_close(simulation.particles.pos(0).x, 46.0);
+}
+
+#[test]
+fn two_pinned_particles_stay_put() {
+    let a = Particle::pinned(Vec2::new(50.0, 50.0), RADIUS);
+    let b = Particle::pinned(Vec2::new(52.0, 50.0), RADIUS);
+    let mut simulation = simulation(&[a, b]);
+    simulation.solve_collisions();
+
+    assert_eq!(simulation.particles.pos(0), Vec2::new(50.0, 50.0));
+    assert_eq!(simulation.particles.pos(1), Vec2::new(52.0, 50.0));
+}
+
+#[test]
+fn resolves_pairs_across_every_cell_border() {
+    // Cells are one diameter wide. Straddle every row border with a vertical
+    // pair, then every column border with a horizontal one, so the pairs land
+    // in different cells, bands and seams. Neighbouring pairs sit in
+    // different lanes so they don't touch each other.
+    let cell = RADIUS * 2.0;
+    for vertical in [true, false] {
+        let mut particles = Vec::new();
+        for k in 1..20 {
+            let border = k as f32 * cell;
+            let lane = 15.0 + (k % 4) as f32 * 50.0;
+            let (a, b) = if vertical {
+                (particle(lane, border - 2.0), particle(lane, border + 2.0))
+            } else {
+                (particle(border - 2.0, lane), particle(border + 2.0, lane))
+            };
+            particles.push(a);
+            particles.push(b);
+        }
+
+        let mut simulation = simulation(&particles);
+        simulation.solve_collisions();
+
+        for i in (0..particles.len()).step_by(2) {
+            assert_close(simulation.particles.pos(i).distance(simulation.particles.pos(i + 1)), RADIUS * 2.0);
+        }
+    }
+}
+
+#[test]
+fn collides_particles_outside_the_world() {
+    // Clamped into the border cells
+    let mut simulation = simulation(&[particle(-30.0, 250.0), particle(-24.0, 250.0)]);
+    simulation.solve_collisions();
+
+    assert_close(simulation.particles.pos(0).distance(simulation.particles.pos(1)), RADIUS * 2.0);
+}
+
+#[test]
+fn mixed_radii_use_the_sum_of_radii() {
+    let big = Particle::new(Vec2::new(60.0, 50.0), Vec2::new(60.0, 50.0), 12.0, 1.0);
+    let mut simulation = simulation(&[particle(50.0, 50.0), big]);
+    simulation.solve_collisions();
+
+    assert_close(simulation.particles.pos(0).distance(simulation.particles.pos(1)), RADIUS + 12.0);
+}
+
+#[test]
+fn keeps_every_particle_and_its_order() {
+    let particles: Vec<Particle> = (0..30)
+        .map(|i| particle(20.0 + (i % 6) as f32 * 7.0, 20.0 + (i / 6) as f32 * 7.0))
+        .collect();
+    let mut simulation = simulation(&particles);
+    for _ in 0..5 {
+        simulation.solve_collisions();
+    }
+
+    assert_eq!(simulation.particles.len(), particles.len());
+    for (before, after) in particles.iter().zip(&simulation.particles) {
+        // Every particle only ever moves a little, so none were swapped
+        assert!(before.pos.distance(after.pos) < RADIUS * 2.0);
+        assert!(after.pos.is_finite());
+    }
+}
+
+#[test]
+fn step_counts_pair_tests_and_contacts() {
+    let mut simulation = simulation(&[particle(150.0, 150.0), particle(156.0, 150.0)]);
+    let stats = simulation.step(1.0 / 60.0);
+
+    assert_eq!(stats.frame, 1);
+    assert_eq!(stats.substeps, 6);
+    assert_eq!(stats.particles, 2);
+    // The pair overlaps in the first substep and flies apart after that
+    assert_eq!(stats.contacts, 1);
+    assert!(stats.pair_tests >= 1);
+}
+
+#[test]
+fn records_contacts_and_pressure_per_particle() {
+    // Pinned in the middle, so solving one pair doesn't change the other's overlap
+    let middle = Particle::pinned(Vec2::new(56.0, 50.0), RADIUS);
+    let mut simulation = simulation(&[particle(50.0, 50.0), middle, particle(62.0, 50.0), particle(150.0, 150.0)]);
+    simulation.solve_collisions();
+
+    let particles = &simulation.particles;
+    assert_eq!((0..4).map(|i| particles.contacts(i)).collect::<Vec<_>>(), [1, 2, 1, 0]);
+    // Each overlap is 4 units deep
+    assert_close(particles.pressure(0), 4.0);
+    assert_close(particles.pressure(1), 8.0);
+    assert_close(particles.pressure(2), 4.0);
+    assert_eq!(particles.pressure(3), 0.0);
+}
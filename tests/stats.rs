@@ -0,0 +1,99 @@
+use std::time::Duration;
+
+use serde_json::Value;
+use verlet::{RollingStats, StatsLog, StepStats};
+
+fn step(frame: u64, micros: u64) -> StepStats {
+    let us = Duration::from_micros;
+    StepStats {
+        frame,
+        substeps: frame as u32,
+        particles: 10 * frame as usize,
+        forces: us(micros),
+        constraints: us(2 * micros),
+        collisions: us(3 * micros),
+        integration: us(4 * micros),
+        pair_tests: 100 * frame,
+        contacts: 10 * frame,
+    }
+}
+
+#[test]
+fn rolling_average_keeps_only_the_window() {
+    let mut rolling = RollingStats::new(3);
+    assert!(rolling.is_empty());
+    assert_eq!(rolling.average(), StepStats::default());
+
+    for frame in 1..=5 {
+        rolling.push(step(frame, 10 * frame));
+    }
+    assert_eq!(rolling.len(), 3);
+
+    // Frames 3, 4 and 5, frame and particles from the latest
+    let average = rolling.average();
+    assert_eq!(average.frame, 5);
+    assert_eq!(average.particles, 50);
+    assert_eq!(average.substeps, 4);
+    assert_eq!(average.forces, Duration::from_micros(40));
+    assert_eq!(average.integration, Duration::from_micros(160));
+    assert_eq!(average.pair_tests, 400);
+    assert_eq!(average.contacts, 40);
+
+    rolling.clear();
+    assert!(rolling.is_empty());
+}
+
+#[test]
+fn csv_has_a_row_per_step_under_the_header() {
+    let mut log = StatsLog::new();
+    log.record(step(1, 10));
+    log.record(step(2, 20));
+
+    let csv = log.to_csv();
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(
+        lines[0],
+        "frame,substeps,particles,forces_us,constraints_us,collisions_us,integration_us,total_us,pair_tests,contacts"
+    );
+    assert_eq!(lines.len(), 3);
+    assert!(lines.iter().all(|line| line.split(',').count() == 10));
+    assert_eq!(lines[2], "2,2,20,20.000,40.000,60.000,80.000,200.000,200,20");
+}
+
+#[test]
+fn trace_phases_run_back_to_back_inside_their_step() {
+    let mut log = StatsLog::new();
+    log.record(step(1, 10));
+    log.record(step(2, 20));
+
+    let trace: Value = serde_json::from_str(&log.to_chrome_trace()).unwrap();
+    assert_eq!(trace["displayTimeUnit"], "ms");
+    let events = trace["traceEvents"].as_array().unwrap();
+    // A step, its four phases and a counter for each step
+    assert_eq!(events.len(), 12);
+
+    let close = |a: f64, b: f64| (a - b).abs() < 1e-6;
+    for (events, micros) in events.chunks(6).zip([10.0, 20.0]) {
+        let ts = |i: usize| events[i]["ts"].as_f64().unwrap();
+        let dur = |i: usize| events[i]["dur"].as_f64().unwrap();
+
+        assert_eq!(events[0]["name"], "step");
+        assert_eq!(events[0]["ph"], "X");
+        assert!(close(dur(0), 10.0 * micros));
+
+        let mut end = ts(0);
+        for (i, name) in ["forces", "constraints", "collisions", "integration"].into_iter().enumerate() {
+            let phase = &events[i + 1];
+            assert_eq!(phase["name"], name);
+            assert_eq!(phase["ph"], "X");
+            assert!(close(ts(i + 1), end), "{phase}");
+            assert!(close(dur(i + 1), (i + 1) as f64 * micros), "{phase}");
+            end += dur(i + 1);
+        }
+        assert!(close(end, ts(0) + dur(0)));
+
+        assert_eq!(events[5]["name"], "solver");
+        assert_eq!(events[5]["ph"], "C");
+        assert!(close(ts(5), end));
+    }
+}
@@ -0,0 +1,49 @@
+use verlet::FixedTimestep;
+
+// A power of two, so the sums below are exact
+const DT: f32 = 1.0 / 64.0;
+
+#[test]
+fn pays_out_whole_steps_and_banks_the_rest() {
+    let mut timestep = FixedTimestep::new(DT, 5);
+
+    assert_eq!(timestep.advance(DT * 0.5), 0);
+    assert_eq!(timestep.alpha(), 0.5);
+    assert_eq!(timestep.advance(DT * 0.75), 1);
+    assert_eq!(timestep.alpha(), 0.25);
+    assert_eq!(timestep.advance(DT * 2.0), 2);
+    assert_eq!(timestep.alpha(), 0.25);
+
+    // Time running backwards is ignored
+    assert_eq!(timestep.advance(-1.0), 0);
+    assert_eq!(timestep.alpha(), 0.25);
+
+    timestep.reset();
+    assert_eq!(timestep.alpha(), 0.0);
+}
+
+#[test]
+fn drops_the_backlog_after_a_long_frame() {
+    let mut timestep = FixedTimestep::new(DT, 5);
+
+    assert_eq!(timestep.advance(DT * 20.5), 5);
+    assert_eq!(timestep.alpha(), 0.0);
+    assert_eq!(timestep.advance(DT), 1);
+}
+
+#[test]
+fn time_scale_raises_the_step_limit() {
+    // 8x on a display running at the step rate owes 8 steps every frame
+    let mut timestep = FixedTimestep::new(DT, 5);
+    let steps: u32 = (0..60).map(|_| timestep.advance_scaled(DT, 8.0)).sum();
+    assert_eq!(steps, 480);
+
+    // Slow motion pays out a step every few frames
+    let mut timestep = FixedTimestep::new(DT, 5);
+    let steps: Vec<u32> = (0..8).map(|_| timestep.advance_scaled(DT, 0.25)).collect();
+    assert_eq!(steps, [0, 0, 0, 1, 0, 0, 0, 1]);
+
+    // The limit still applies, scaled
+    let mut timestep = FixedTimestep::new(DT, 5);
+    assert_eq!(timestep.advance_scaled(DT * 100.0, 2.0), 10);
+}